@@ -0,0 +1,15 @@
+//! Compile-time helpers for embedding ANSI sequences in generated code.
+//!
+//! The style engine itself lives in `cio-runtime` so that styles resolved
+//! while expanding a macro and dynamic styles resolved at runtime share a
+//! single implementation. This module adds the string escaping needed to
+//! place rendered text inside the generated string literals:
+//! - Backslash escaping: `\` → `\\`
+//! - Quote escaping: `"` → `\"`
+//! - Newline escaping: `\n` → `\\n`
+
+pub use cio_runtime::{ansi_code_for_style, split_style_list, Color};
+
+pub fn escape_string(s: &str) -> String {
+    s.replace("\\", "\\\\").replace("\"", "\\\"").replace("\n", "\\n")
+}
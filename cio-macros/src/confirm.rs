@@ -0,0 +1,62 @@
+//! Procedural macro for yes/no questions.
+//!
+//! `confirm!` appends a `[y/n]` hint to the prompt, reads the answer and
+//! returns a `bool`. Answers are case-insensitive; `y`, `yes`, `n` and `no`
+//! are always accepted.
+//!
+//! # Options
+//! - `default = <bool>` - Value of an empty answer; the hint becomes `[Y/n]`
+//!   or `[y/N]`
+//! - `yes = [...]`, `no = [...]` - Extra words accepted by this call
+//!
+//! # Examples
+//!
+//! ## Basic Confirmation
+//! if confirm!("@(red, bold)Delete all files?", default = false) { ... }
+//!
+//! ## Additional Words
+//! let ok = confirm!("Continuer ?", yes = ["oui", "o"], no = ["non"]);
+//!
+//! # Technical Notes
+//!
+//! - `yes = [...]` / `no = [...]` add words to one call only
+//! - `cio::runtime::add_confirm_words` adds words to every call in the
+//!   program; both kinds are accepted alongside `y`/`yes` and `n`/`no`
+//! - Invalid answers, EOF and I/O errors are handled exactly as in `input!`
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::LitStr;
+use syn::parse::Parser;
+use crate::input::{self, InputArgs};
+
+const CONFIRM_OPTIONS: [&str; 3] = ["default", "yes", "no"];
+
+pub fn confirm_impl(input: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| InputArgs::parse_with(input, &CONFIRM_OPTIONS, &[]);
+    let args = match parser.parse(input) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let value = args.prompt.value();
+    let prompt = LitStr::new(&format!("{}@(dimmed)[ [{{__cio_hint}}]] ", value.trim_end()), args.prompt.span());
+    let default = match args.option("default") {
+        Some(default) => quote! { ::std::option::Option::Some(#default) },
+        None => quote! { ::std::option::Option::None },
+    };
+    let words = |key: &str| match args.option(key) {
+        Some(words) => quote! { &(#words) },
+        None => quote! { &[] },
+    };
+    let (yes, no) = (words("yes"), words("no"));
+    let read_answer = input::retry_loop(quote! {
+        ::cio::runtime::write_output(&::cio::format!(#prompt))
+            .and_then(|()| ::cio::runtime::read_input_line())
+            .and_then(|line| ::cio::runtime::parse_confirm(&line, __cio_default, #yes, #no))
+    }, quote! {});
+    quote! {{
+        let __cio_default: ::std::option::Option<bool> = #default;
+        let __cio_hint = ::cio::runtime::confirm_hint(__cio_default);
+        #read_answer
+    }}.into()
+}
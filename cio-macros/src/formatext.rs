@@ -0,0 +1,824 @@
+//! Format string processing module for the println! macro.
+//!
+//! This module handles the parsing and generation of formatted output
+//! with support for ANSI colors, custom formatting, and variable interpolation.
+//! It's the core engine behind the enhanced formatting capabilities.
+//!
+//! # Architecture
+//!
+//! The module operates in two main phases:
+//! 1. **Parsing**: Converts format strings into structured FormatToken sequences
+//! 2. **Generation**: Transforms tokens into executable Rust code for output
+//!
+//! # Token Types
+//!
+//! - **StyleChange**: ANSI color/style modifications like `@(red, bold)`
+//! - **StyleReset**: Style reset command `@()`  
+//! - **StyleVariable**: Dynamic style from variables `@(color_var)`
+//! - **ScopeStart/ScopeEnd**: Style scope `@(red)[...]`; the style inside
+//!   is layered on the enclosing one and restored at the closing `]`.
+//!   `@(red)[[` is not a scope but a style change followed by a literal `[`
+//! - **Text**: Plain text content between format specifiers
+//! - **Variable**: Data interpolation with optional formatting `{var:format}`
+//!
+//! # Format Specifiers
+//!
+//! ## Basic Formatting
+//! - `:a` - Array format with proper indentation for nested structures
+//! - `:c` - Compact single-line format using Debug trait
+//! - `:j` - JSON-like pretty format with multi-line indentation
+//!
+//! ## Mathematical Formatting  
+//! - `:m` - Matrix format with proper borders for 2D arrays
+//! - `:d` - Determinant format with vertical bars for mathematical notation
+//!
+//! ## Table Formatting
+//! - `:t` - Smart table format with automatic structure detection
+//! - `:t(Col1, Col2)` - Table with custom column headers
+//! - `:t(width=80, overflow=truncate)` - Table options, mixed freely with
+//!   headers: maximum width and how cells that do not fit are shortened
+//! - `:t[style=ascii]`, `:t(Col1, Col2)[style=rounded]` - Table options in
+//!   brackets, kept apart from the headers; `style` picks the border style
+//!
+//! ## Table Export
+//! - `:md` - GitHub-flavoured Markdown table
+//! - `:csv`, `:tsv` - RFC 4180 comma/tab-separated values
+//! - `:html` - HTML `<table>` with `colspan` for multi-level headers
+//! - `:csv(Col1, Col2)` - Custom column headers, as with `:t`
+//!
+//! ## Row Labels
+//! - `:t(labels=first)` - Always show the first column as row labels
+//! - `:t(labels=none)` - Never; the default (`auto`) follows the data shape
+//!
+//! # Style Processing
+//!
+//! The module recognizes these color and style terms:
+//!
+//! **Colors**: black, red, green, yellow, blue, magenta, cyan, white
+//! **Bright Colors**: bright_red, bright_green, bright_blue, etc.
+//! **Styles**: bold, italic, underline, dimmed, blink, reversed, hidden, strikethrough
+//!
+//! # Variable Detection
+//!
+//! Variables are automatically tracked for unused variable suppression:
+//! - Simple identifiers: `name`, `age`, `data`
+//! - Complex expressions with operators are ignored to prevent false positives
+//! - Style variables in `@({var})` syntax are handled separately
+//!
+//! # Code Generation
+//!
+//! The module generates optimized Rust code that:
+//! - Minimizes string allocations through result buffer reuse
+//! - Handles ANSI escape sequences efficiently
+//! - Provides proper error handling for formatting operations
+//! - Integrates seamlessly with extension modules for specialized formats
+//!
+//! # Extension Integration
+//!
+//! Format processing delegates to the `cio::runtime` support crate:
+//! - Basic formats (`:a`) → `format_container`
+//! - Mathematical formats (`:m`, `:d`) → `format_matrix`, `format_determinant`
+//! - Table formats (`:t`) → `format_table` with color hierarchies
+//!
+//! # Technical Implementation
+//!
+//! - Uses a hand-written single-pass parser that tracks byte positions
+//! - Reports malformed strings as `compile_error!` pointing into the literal
+//!   (unknown specs, unclosed `{` / `@(`, unknown colors with suggestions)
+//! - Maintains a stack of style layers throughout token processing; layers
+//!   known at compile time are merged into a single literal sequence
+//! - Generates compile-time verified Rust code
+//! - Supports both newline and no-newline output modes
+
+use crate::colorstyle::{self, split_style_list, Color};
+use cio_runtime::{BorderStyle, Labels, Overflow, TableFormat};
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+use syn::LitStr;
+
+#[derive(Clone, Debug)]
+pub enum FormatToken {
+    StyleChange { style_specs: Vec<String> },
+    StyleReset,
+    Text { content: String },
+    Variable {
+        name: String,
+        format: Option<String>,
+        format_args: Option<Vec<String>>,
+    },
+    StyleVariable {
+        name: String,
+    },
+    ScopeStart,
+    ScopeEnd,
+}
+const KNOWN_COLORS: [&str; 17] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    "bright_black", "gray", "bright_red", "bright_green", "bright_yellow",
+    "bright_blue", "bright_magenta", "bright_cyan", "bright_white"
+];
+const KNOWN_STYLES: [&str; 8] = [
+    "bold", "italic", "underline", "dimmed", "blink", "reversed", "hidden", "strikethrough"
+];
+const CIO_SPECS: [&str; 10] = ["a", "c", "j", "m", "d", "t", "md", "csv", "tsv", "html"];
+const EXPORT_SPECS: [&str; 4] = ["md", "csv", "tsv", "html"];
+const BACKGROUND_PREFIX: &str = "on_";
+static STD_SPEC: LazyLock<Regex> = LazyLock::new(|| Regex::new(
+    r"^(?:.?[<^>])?[+-]?#?0?(?:\d+\$?|[A-Za-z_][A-Za-z0-9_]*\$)?(?:\.(?:\d+\$?|[A-Za-z_][A-Za-z0-9_]*\$|\*))?(?:[xX]?\?|[xXobeE])?$"
+).unwrap());
+static IDENT_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[A-Za-z_][A-Za-z0-9_]*").unwrap());
+static COUNT_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"([A-Za-z_][A-Za-z0-9_]*|\d+)\$").unwrap());
+fn is_known_term(term: &str) -> bool {
+    let trimmed = term.trim();
+    let color = trimmed.strip_prefix(BACKGROUND_PREFIX).unwrap_or(trimmed);
+    color.parse::<Color>().is_ok() || KNOWN_STYLES.contains(&trimmed)
+}
+fn is_extended_color(term: &str) -> bool {
+    let color = term.strip_prefix(BACKGROUND_PREFIX).unwrap_or(term);
+    color.starts_with('#') || color.starts_with("rgb(") || color.starts_with("color(")
+}
+fn is_style_list(expr: &str) -> bool {
+    if expr.is_empty() {
+        return false;
+    }
+    split_style_list(expr).iter().any(|term| is_known_term(term) || is_extended_color(term))
+}
+pub struct FormatError {
+    pub message: String,
+    pub range: Range<usize>,
+}
+impl FormatError {
+    fn new(message: impl Into<String>, range: Range<usize>) -> Self {
+        FormatError { message: message.into(), range }
+    }
+    pub fn to_syn_error(&self, lit: &LitStr) -> syn::Error {
+        let token = lit.token();
+        let source = token.to_string();
+        let value = lit.value();
+        let subspan = literal_content_offset(&source, &value)
+            .and_then(|offset| token.subspan(offset + self.range.start..offset + self.range.end));
+        if let Some(span) = subspan {
+            return syn::Error::new(span, &self.message);
+        }
+        let start = self.range.start.min(value.len());
+        let end = self.range.end.clamp(start, value.len());
+        let line_start = value[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = value[start..].find('\n').map_or(value.len(), |i| start + i);
+        let pad = value[line_start..start].chars().count();
+        let width = value[start..end.min(line_end)].chars().count().max(1);
+        syn::Error::new(lit.span(), format!("{}\n | {}\n | {}{}",
+            self.message, value[line_start..line_end].replace('\t', " "), " ".repeat(pad), "^".repeat(width)))
+    }
+}
+fn literal_content_offset(source: &str, value: &str) -> Option<usize> {
+    let hashes = source.strip_prefix('r').map(|raw| raw.len() - raw.trim_start_matches('#').len());
+    let prefix = hashes.map_or(1, |n| n + 2);
+    let suffix = hashes.map_or(1, |n| n + 1);
+    let content = source.get(prefix..source.len().checked_sub(suffix)?)?;
+    (content == value).then_some(prefix)
+}
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut before: Vec<usize> = Vec::new();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (prev[j] + 1).min(row[j - 1] + 1).min(prev[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(before[j - 2] + 1);
+            }
+        }
+        before = std::mem::replace(&mut prev, row);
+    }
+    prev[b.len()]
+}
+pub fn suggest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    candidates.into_iter()
+        .map(|c| (edit_distance(&word, c), c))
+        .filter(|(d, _)| *d <= 2 && *d < word.chars().count())
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+fn suggest_style(term: &str) -> Option<String> {
+    if let Some(color) = term.strip_prefix(BACKGROUND_PREFIX) {
+        return suggest(color, KNOWN_COLORS.iter().copied()).map(|c| format!("{}{}", BACKGROUND_PREFIX, c));
+    }
+    suggest(term, KNOWN_COLORS.iter().chain(KNOWN_STYLES.iter()).copied()).map(String::from)
+}
+fn find_closing(s: &str, from: usize, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (i, c) in s[from..].char_indices() {
+        if in_quotes {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_quotes = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' if open == '{' => in_quotes = true,
+            _ if c == open => depth += 1,
+            _ if c == close && depth == 0 => return Some(from + i),
+            _ if c == close => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+fn find_spec_colon(content: &str) -> Option<usize> {
+    let bytes = content.as_bytes();
+    let mut depth = 0;
+    let mut in_quotes = false;
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'"' => in_quotes = !in_quotes,
+            _ if in_quotes => {}
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            b':' if depth == 0 => {
+                let double = bytes.get(i + 1) == Some(&b':') || (i > 0 && bytes[i - 1] == b':');
+                if !double { return Some(i); }
+            },
+            _ => {}
+        }
+    }
+    None
+}
+fn split_terms(content: &str, offset: usize) -> Vec<(String, Range<usize>)> {
+    let mut terms = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    for (i, c) in content.char_indices().chain(std::iter::once((content.len(), ','))) {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if c == ',' && depth == 0 {
+            let raw = &content[start..i];
+            let lead = raw.len() - raw.trim_start().len();
+            let trimmed = raw.trim();
+            let term_start = offset + start + lead;
+            terms.push((trimmed.to_string(), term_start..term_start + trimmed.len()));
+            start = i + 1;
+        }
+    }
+    terms
+}
+fn parse_style(fmt_str: &str, start: usize, end: usize, used_vars: &mut Vec<String>) -> Result<FormatToken, FormatError> {
+    let content = &fmt_str[start..end];
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return Ok(FormatToken::StyleReset);
+    }
+    if trimmed.starts_with('{') && trimmed.ends_with('}') {
+        let var_name = trimmed[1..trimmed.len()-1].trim();
+        used_vars.push(var_name.to_string());
+        return Ok(FormatToken::StyleVariable { name: var_name.to_string() });
+    }
+    let terms = split_terms(content, start);
+    if !is_style_list(content) && terms.len() == 1 && suggest_style(trimmed).is_none()
+        && syn::parse_str::<syn::Expr>(trimmed).is_ok() {
+        return Ok(FormatToken::StyleVariable { name: trimmed.to_string() });
+    }
+    for (term, range) in &terms {
+        if is_known_term(term) {
+            continue;
+        }
+        let message = match suggest_style(term) {
+            _ if is_extended_color(term) => format!(
+                "invalid color `{}` (expected #rrggbb, rgb(r, g, b) with components 0-255, or color(0-255))", term),
+            Some(known) => format!("unknown color or style `{}`, did you mean `{}`?", term, known),
+            None if term.is_empty() => "empty entry in style list".to_string(),
+            None => format!("unknown color or style `{}` (use `@({{{}}})` for a dynamic style)", term, term),
+        };
+        let range = if range.is_empty() { start..end } else { range.clone() };
+        return Err(FormatError::new(message, range));
+    }
+    Ok(FormatToken::StyleChange {
+        style_specs: terms.into_iter().map(|(term, _)| term).collect(),
+    })
+}
+fn parse_placeholder(fmt_str: &str, start: usize, end: usize, used_vars: &mut Vec<String>) -> Result<FormatToken, FormatError> {
+    let content = &fmt_str[start..end];
+    let (raw_name, spec) = match find_spec_colon(content) {
+        Some(colon) => (&content[..colon], Some((start + colon + 1, &content[colon + 1..]))),
+        None => (content, None),
+    };
+    let name = raw_name.trim();
+    if !name.is_empty() && !name.chars().all(|c| c.is_ascii_digit()) {
+        if syn::parse_str::<syn::Expr>(name).is_err() {
+            let lead = raw_name.len() - raw_name.trim_start().len();
+            return Err(FormatError::new(format!("invalid expression `{}` in placeholder", name),
+                                        start + lead..start + lead + name.len()));
+        }
+        if !name.contains(" ") && !name.contains("*") && !name.contains("+")
+            && !name.contains("-") && !name.contains("/") && !name.contains(".") {
+            used_vars.push(name.to_string());
+        }
+    }
+    let (format, format_args) = match spec {
+        None => (None, None),
+        Some((spec_start, spec)) => parse_spec(spec, spec_start)?,
+    };
+    Ok(FormatToken::Variable { name: name.to_string(), format, format_args })
+}
+const TABLE_OPTIONS: [&str; 4] = ["width", "overflow", "style", "labels"];
+pub fn table_option(arg: &str) -> Option<(&str, &str)> {
+    let (key, value) = arg.split_once('=')?;
+    let key = key.trim();
+    (!key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')).then(|| (key, value.trim()))
+}
+fn check_table_option(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "width" => match value.parse::<usize>() {
+            Ok(width) if width > 0 => Ok(()),
+            _ => Err(format!("invalid table width `{}` (expected a positive number of columns)", value)),
+        },
+        "overflow" => value.parse::<Overflow>().map(|_| ()),
+        "style" => value.parse::<BorderStyle>().map(|_| ()),
+        "labels" => value.parse::<Labels>().map(|_| ()),
+        _ => Err(match suggest(key, TABLE_OPTIONS) {
+            Some(known) => format!("unknown table option `{}`, did you mean `{}`?", key, known),
+            None => format!("unknown table option `{}` (expected one of `width`, `overflow`, `style`, `labels`)", key),
+        }),
+    }
+}
+fn table_headers(args: &[String]) -> String {
+    let headers = args.iter()
+        .filter(|arg| table_option(arg).is_none())
+        .map(|header| format!("::std::string::String::from(\"{}\")", colorstyle::escape_string(header)))
+        .collect::<Vec<_>>();
+    if headers.is_empty() {
+        "::std::vec::Vec::<::std::string::String>::new()".to_string()
+    } else {
+        format!("::std::vec![{}]", headers.join(", "))
+    }
+}
+fn table_call(name: &str, args: Option<&[String]>, to_stderr: bool) -> String {
+    let args = args.unwrap_or_default();
+    let headers = table_headers(args);
+    let mut settings: Vec<String> = args.iter().filter_map(|arg| table_option(arg)).map(|(key, value)| match key {
+        "overflow" => format!(".overflow({})", match value.parse::<Overflow>() {
+            Ok(Overflow::Truncate) => "::cio::runtime::Overflow::Truncate",
+            _ => "::cio::runtime::Overflow::Wrap",
+        }),
+        "style" => format!(".style(::cio::runtime::BorderStyle::{:?})", value.parse::<BorderStyle>().unwrap_or_default()),
+        "labels" => format!(".labels(::cio::runtime::Labels::{:?})", value.parse::<Labels>().unwrap_or_default()),
+        _ => format!(".{}({})", key, value),
+    }).collect();
+    if to_stderr {
+        settings.push(".stream(::cio::runtime::OutputStream::Stderr)".to_string());
+    }
+    if settings.is_empty() {
+        format!("::cio::runtime::format_table(&{}, &{})", name, headers)
+    } else {
+        format!("::cio::runtime::format_table_with(&{}, &{}, &::cio::runtime::TableOptions::new(){})", name, headers, settings.concat())
+    }
+}
+fn export_call(kind: &str, name: &str, args: Option<&[String]>) -> String {
+    let args = args.unwrap_or_default();
+    let format = kind.parse::<TableFormat>().unwrap_or(TableFormat::Markdown);
+    let labels = args.iter().filter_map(|arg| table_option(arg)).find(|(key, _)| *key == "labels")
+        .map(|(_, value)| value.parse::<Labels>().unwrap_or_default());
+    match labels {
+        Some(labels) => format!("::cio::runtime::export_table_with(&{}, &{}, ::cio::runtime::TableFormat::{:?}, &::cio::runtime::TableOptions::new().labels(::cio::runtime::Labels::{:?}))",
+                                name, table_headers(args), format, labels),
+        None => format!("::cio::runtime::export_table(&{}, &{}, ::cio::runtime::TableFormat::{:?})", name, table_headers(args), format),
+    }
+}
+fn check_export_option(kind: &str, key: &str, range: &Range<usize>) -> Result<(), FormatError> {
+    if kind != "t" && key != "labels" {
+        return Err(FormatError::new(format!("`:{}` takes column headers and `labels` only, `{}` applies to `:t`", kind, key), range.clone()));
+    }
+    Ok(())
+}
+fn parse_table_args(kind: &str, args: &str, args_start: usize) -> Result<Vec<String>, FormatError> {
+    let mut headers = Vec::new();
+    for (header, range) in split_terms(args, args_start) {
+        if header.is_empty() {
+            let range = if range.is_empty() { args_start - 1..args_start + args.len() + 1 } else { range };
+            return Err(FormatError::new("empty column header in `:t(...)`", range));
+        }
+        if let Some((key, value)) = table_option(&header) {
+            check_export_option(kind, key, &range)?;
+            check_table_option(key, value).map_err(|message| FormatError::new(message, range))?;
+        }
+        headers.push(header);
+    }
+    Ok(headers)
+}
+fn parse_spec(spec: &str, spec_start: usize) -> Result<(Option<String>, Option<Vec<String>>), FormatError> {
+    let (spec, options) = match spec.find('[') {
+        Some(open) if spec.ends_with(']') => (&spec[..open], Some((spec_start + open + 1, &spec[open + 1..spec.len() - 1]))),
+        _ => (spec, None),
+    };
+    let (kind, args) = match spec.find('(') {
+        Some(open) => {
+            if !spec.ends_with(')') {
+                return Err(FormatError::new("unclosed `(` in format arguments",
+                                            spec_start + open..spec_start + spec.len()));
+            }
+            (&spec[..open], Some((spec_start + open + 1, &spec[open + 1..spec.len() - 1])))
+        },
+        None => (spec, None),
+    };
+    if let Some((options_start, options)) = options {
+        if kind != "t" && !EXPORT_SPECS.contains(&kind) {
+            return Err(FormatError::new(format!("format specifier `:{}` does not take options", kind),
+                                        options_start - 1..options_start + options.len() + 1));
+        }
+        let mut settings = match args {
+            Some((args_start, args)) => parse_table_args(kind, args, args_start)?,
+            None => Vec::new(),
+        };
+        for (option, range) in split_terms(options, options_start) {
+            let Some((key, value)) = table_option(&option) else {
+                let range = if range.is_empty() { options_start - 1..options_start + options.len() + 1 } else { range };
+                return Err(FormatError::new(format!("expected `key=value` in `:{}[...]`, found `{}`", kind, option), range));
+            };
+            check_export_option(kind, key, &range)?;
+            check_table_option(key, value).map_err(|message| FormatError::new(message, range))?;
+            settings.push(option);
+        }
+        return Ok((Some(kind.to_string()), Some(settings)));
+    }
+    if CIO_SPECS.contains(&kind) {
+        let Some((args_start, args)) = args else {
+            return Ok((Some(kind.to_string()), None));
+        };
+        if kind != "t" && !EXPORT_SPECS.contains(&kind) {
+            return Err(FormatError::new(format!("format specifier `:{}` does not take arguments", kind),
+                                        args_start - 1..args_start + args.len() + 1));
+        }
+        return Ok((Some(kind.to_string()), Some(parse_table_args(kind, args, args_start)?)));
+    }
+    if args.is_none() && STD_SPEC.is_match(spec) {
+        return Ok((Some(spec.to_string()), None));
+    }
+    let range = spec_start..spec_start + kind.len().max(1);
+    let message = match suggest(kind, CIO_SPECS) {
+        Some(known) => format!("unknown format specifier `:{}`, did you mean `:{}`?", kind, known),
+        None => format!("unknown format specifier `:{}` (expected one of `:a`, `:c`, `:j`, `:m`, `:d`, `:t`, `:md`, `:csv`, `:tsv`, `:html` or a std format spec)", kind),
+    };
+    Err(FormatError::new(message, range))
+}
+pub fn parse_format_string(fmt_str: &str) -> Result<(Vec<FormatToken>, Vec<String>), FormatError> {
+    let mut tokens = Vec::new();
+    let mut used_vars = Vec::new();
+    let mut text = String::new();
+    let mut scopes: Vec<(Range<usize>, usize)> = Vec::new();
+    let mut i = 0;
+    while i < fmt_str.len() {
+        let rest = &fmt_str[i..];
+        if let Some(escape) = ["{{", "}}", "@@(", "$$("].iter().find(|e| rest.starts_with(**e)) {
+            text.push_str(&escape[1..]);
+            i += escape.len();
+            continue;
+        }
+        if rest.starts_with('}') {
+            return Err(FormatError::new("unmatched `}` in format string (use `}}` for a literal `}`)", i..i + 1));
+        }
+        let mut opens_scope = false;
+        let token = if rest.starts_with("@(") {
+            let close = find_closing(fmt_str, i + 2, '(', ')')
+                .ok_or_else(|| FormatError::new("unclosed style: expected `)` to end `@(`", i..i + 2))?;
+            let token = parse_style(fmt_str, i + 2, close, &mut used_vars)?;
+            let escaped_bracket = fmt_str[close + 1..].starts_with("[[");
+            opens_scope = fmt_str[close + 1..].starts_with('[') && !escaped_bracket;
+            if opens_scope {
+                if matches!(token, FormatToken::StyleReset) {
+                    return Err(FormatError::new("`@()` cannot open a style scope", i..close + 2));
+                }
+                scopes.push((i..close + 2, 0));
+            }
+            i = close + 1 + usize::from(opens_scope || escaped_bracket);
+            token
+        } else if rest.starts_with('{') {
+            let close = find_closing(fmt_str, i + 1, '{', '}')
+                .ok_or_else(|| FormatError::new("unclosed placeholder: expected `}` to end `{`", i..i + 1))?;
+            let token = parse_placeholder(fmt_str, i + 1, close, &mut used_vars)?;
+            i = close + 1;
+            token
+        } else if rest.starts_with(']') && scopes.last().is_some_and(|(_, depth)| *depth == 0) {
+            scopes.pop();
+            i += 1;
+            FormatToken::ScopeEnd
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            if let Some((_, depth)) = scopes.last_mut() {
+                match c {
+                    '[' => *depth += 1,
+                    ']' => *depth -= 1,
+                    _ => {}
+                }
+            }
+            text.push(c);
+            i += c.len_utf8();
+            continue;
+        };
+        if !text.is_empty() {
+            tokens.push(FormatToken::Text { content: std::mem::take(&mut text) });
+        }
+        if opens_scope {
+            tokens.push(FormatToken::ScopeStart);
+        }
+        tokens.push(token);
+    }
+    if let Some((range, _)) = scopes.pop() {
+        return Err(FormatError::new("unclosed style scope: expected `]` to end `@(...)[`", range));
+    }
+    if !text.is_empty() {
+        tokens.push(FormatToken::Text { content: text });
+    }
+    Ok((tokens, used_vars))
+}
+pub struct ArgumentUsage {
+    pub positional: Vec<bool>,
+    pub named: Vec<bool>,
+}
+pub fn positional_binding(index: usize) -> String {
+    format!("__cio_arg_{}", index)
+}
+fn bind_positional(usage: &mut ArgumentUsage, index: usize) -> Result<String, String> {
+    match usage.positional.len() {
+        n if index < n => {
+            usage.positional[index] = true;
+            Ok(positional_binding(index))
+        },
+        0 => Err(format!("invalid reference to positional argument {} (no arguments were given)", index)),
+        1 => Err(format!("invalid reference to positional argument {} (there is 1 argument)", index)),
+        n => Err(format!("invalid reference to positional argument {} (there are {} arguments)", index, n)),
+    }
+}
+pub fn bind_arguments(tokens: &mut [FormatToken], used_vars: &mut Vec<String>,
+                      n_positional: usize, named: &[String]) -> Result<ArgumentUsage, String> {
+    let mut usage = ArgumentUsage { positional: vec![false; n_positional], named: vec![false; named.len()] };
+    let mut next_implicit = 0;
+    for token in tokens.iter_mut() {
+        let (name, format) = match token {
+            FormatToken::Variable { name, format, .. } => (name, format.as_mut()),
+            FormatToken::StyleVariable { name } => (name, None),
+            _ => continue,
+        };
+        let trimmed = name.trim();
+        if trimmed.is_empty() {
+            next_implicit += 1;
+            *name = bind_positional(&mut usage, next_implicit - 1)?;
+        } else if let Ok(index) = trimmed.parse::<usize>() {
+            *name = bind_positional(&mut usage, index)?;
+        } else {
+            for ident in IDENT_PATTERN.find_iter(trimmed) {
+                if let Some(i) = named.iter().position(|n| n == ident.as_str()) {
+                    usage.named[i] = true;
+                }
+            }
+        }
+        let Some(format) = format else { continue };
+        let mut rewritten = String::new();
+        let mut last = 0;
+        for cap in COUNT_PATTERN.captures_iter(format) {
+            let whole = cap.get(0).unwrap();
+            let arg = &cap[1];
+            rewritten.push_str(&format[last..whole.start()]);
+            match arg.parse::<usize>() {
+                Ok(index) => rewritten.push_str(&bind_positional(&mut usage, index)?),
+                Err(_) => {
+                    if let Some(i) = named.iter().position(|n| n == arg) { usage.named[i] = true; }
+                    rewritten.push_str(arg);
+                },
+            }
+            rewritten.push('$');
+            last = whole.end();
+        }
+        rewritten.push_str(&format[last..]);
+        *format = rewritten;
+    }
+    used_vars.retain(|v| !v.is_empty() && !v.chars().all(|c| c.is_ascii_digit()));
+    Ok(usage)
+}
+enum StyleLayer {
+    Static(Vec<String>),
+    Dynamic(String),
+}
+fn style_code(layers: &[StyleLayer]) -> String {
+    let mut code = "__cio_result.push_str(\"\\x1B[0m\");".to_string();
+    if layers.is_empty() {
+        return code;
+    }
+    if layers.iter().all(|layer| matches!(layer, StyleLayer::Static(_))) {
+        let terms: Vec<String> = layers.iter()
+            .flat_map(|layer| match layer {
+                StyleLayer::Static(terms) => terms.clone(),
+                StyleLayer::Dynamic(_) => Vec::new(),
+            })
+            .collect();
+        let ansi = colorstyle::ansi_code_for_style(&terms);
+        code.push_str(&format!(" __cio_result.push_str(\"{}\");", colorstyle::escape_string(&ansi)));
+        return code;
+    }
+    let patches: String = layers.iter()
+        .map(|layer| match layer {
+            StyleLayer::Static(terms) => format!("\"{}\"", colorstyle::escape_string(&terms.join(", "))),
+            StyleLayer::Dynamic(name) => format!("({})", name),
+        })
+        .map(|style| format!(".patch(&::cio::runtime::ToStyle::to_style(&{}))", style))
+        .collect();
+    code.push_str(&format!(" __cio_result.push_str(&::cio::runtime::Style::new(){}.ansi_code());", patches));
+    code
+}
+pub fn generate_output_code(tokens: &[FormatToken], to_stderr: bool) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut scopes: Vec<usize> = Vec::new();
+    let mut opening_scope = false;
+    let mut layers: Vec<StyleLayer> = Vec::new();
+    for token in tokens {
+        let scope_base = scopes.last().map_or(0, |restore| restore + 1);
+        match token {
+            FormatToken::StyleChange { .. } | FormatToken::StyleVariable { .. } => {
+                if std::mem::take(&mut opening_scope) {
+                    scopes.push(layers.len());
+                } else {
+                    layers.truncate(scope_base);
+                }
+                layers.push(match token {
+                    FormatToken::StyleChange { style_specs } => StyleLayer::Static(style_specs.clone()),
+                    FormatToken::StyleVariable { name } => StyleLayer::Dynamic(name.clone()),
+                    _ => unreachable!(),
+                });
+                segments.push(style_code(&layers));
+            },
+            FormatToken::StyleReset => {
+                layers.truncate(scope_base);
+                segments.push(style_code(&layers));
+            },
+            FormatToken::ScopeStart => opening_scope = true,
+            FormatToken::ScopeEnd => {
+                layers.truncate(scopes.pop().unwrap_or(0));
+                segments.push(style_code(&layers));
+            },
+            FormatToken::Text { content } => {
+                if !content.is_empty() {
+                    segments.push(format!("__cio_result.push_str(\"{}\");", colorstyle::escape_string(content)));
+                }
+            },
+            FormatToken::Variable { name, format, format_args } => {
+                let format_code = match format.as_deref() {
+                    Some("a") => format!("::cio::runtime::format_container(&{})", name),
+                    Some("c") => format!("::std::format!(\"{{:?}}\", {})", name),
+                    Some("j") => format!("::std::format!(\"{{:#?}}\", {})", name),
+                    Some("m") => format!("::cio::runtime::format_matrix(&{})", name),
+                    Some("d") => format!("::cio::runtime::format_determinant(&{})", name),
+                    Some("t") => table_call(name, format_args.as_deref(), to_stderr),
+                    Some(kind) if EXPORT_SPECS.contains(&kind) => export_call(kind, name, format_args.as_deref()),
+                    Some(fmt) => format!("::std::format!(\"{{:{}}}\", {})", fmt, name),
+                    None => format!("::std::format!(\"{{}}\", {})", name),
+                };
+                segments.push(format!("__cio_result.push_str(&({}));", format_code));
+            },
+        }
+    }
+    let styled = tokens.iter().any(|t| matches!(t,
+        FormatToken::StyleChange { .. } | FormatToken::StyleVariable { .. } | FormatToken::StyleReset));
+    if styled {
+        segments.push("__cio_result.push_str(\"\\x1B[0m\");".to_string());
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(fmt_str: &str) -> Vec<FormatToken> {
+        match parse_format_string(fmt_str) {
+            Ok((tokens, _)) => tokens,
+            Err(e) => panic!("`{}` failed to parse: {}", fmt_str, e.message),
+        }
+    }
+    fn error(fmt_str: &str) -> (String, Range<usize>) {
+        match parse_format_string(fmt_str) {
+            Ok((tokens, _)) => panic!("`{}` parsed as {:?}", fmt_str, tokens),
+            Err(e) => (e.message, e.range),
+        }
+    }
+    fn bind(fmt_str: &str, n_positional: usize, named: &[&str]) -> Result<(Vec<FormatToken>, ArgumentUsage), String> {
+        let (mut tokens, mut used_vars) = parse_format_string(fmt_str).map_err(|e| e.message)?;
+        let named: Vec<String> = named.iter().map(|n| n.to_string()).collect();
+        let usage = bind_arguments(&mut tokens, &mut used_vars, n_positional, &named)?;
+        Ok((tokens, usage))
+    }
+
+    #[test]
+    fn unknown_specs_are_reported() {
+        let (message, range) = error("value: {x:tt}");
+        assert_eq!(message, "unknown format specifier `:tt`, did you mean `:t`?");
+        assert_eq!(range, 10..12);
+        assert!(error("{x:zz}").0.starts_with("unknown format specifier `:zz` (expected one of"));
+        assert_eq!(error("{x:m(A)}").0, "format specifier `:m` does not take arguments");
+        assert_eq!(error("{x:a[style=ascii]}").0, "format specifier `:a` does not take options");
+        assert_eq!(
+            error("{x:t[style=fancy]}").0,
+            "unknown table style `fancy` (expected `light`, `ascii`, `rounded`, `heavy`, `double`, `compact` or `markdown`)",
+        );
+    }
+
+    #[test]
+    fn suggestions_count_swapped_letters_as_one_edit() {
+        assert_eq!(suggest("blod", KNOWN_COLORS.iter().chain(KNOWN_STYLES.iter()).copied()), Some("bold"));
+        assert_eq!(error("@(red, blod)x").0, "unknown color or style `blod`, did you mean `bold`?");
+        assert_eq!(error("@(on_rde)x").0, "unknown color or style `on_rde`, did you mean `on_red`?");
+        assert_eq!(error("@(itlaic)x").0, "unknown color or style `itlaic`, did you mean `italic`?");
+        assert_eq!(edit_distance("blod", "blue"), 2);
+        assert_eq!(edit_distance("ca", "abc"), 3);
+    }
+
+    #[test]
+    fn unclosed_delimiters_are_reported() {
+        assert_eq!(error("total {x"), ("unclosed placeholder: expected `}` to end `{`".to_string(), 6..7));
+        assert_eq!(error("@(red bold"), ("unclosed style: expected `)` to end `@(`".to_string(), 0..2));
+        assert_eq!(error("a } b").0, "unmatched `}` in format string (use `}}` for a literal `}`)");
+        assert_eq!(error("@(red)[warning").0, "unclosed style scope: expected `]` to end `@(...)[`");
+        assert_eq!(error("{x:t(Name}").0, "unclosed `(` in format arguments");
+    }
+
+    #[test]
+    fn escapes_produce_literal_text() {
+        let tokens = tokens("{{x}} @@(red) $$(sep)");
+        assert!(matches!(&tokens[..], [FormatToken::Text { content }] if content == "{x} @(red) $(sep)"));
+    }
+
+    #[test]
+    fn bracket_after_a_style_opens_a_scope() {
+        let scope = tokens("@(red)[a[0]] b");
+        assert!(matches!(&scope[..], [
+            FormatToken::ScopeStart,
+            FormatToken::StyleChange { style_specs },
+            FormatToken::Text { content: inner },
+            FormatToken::ScopeEnd,
+            FormatToken::Text { content: outer },
+        ] if style_specs == &["red"] && inner == "a[0]" && outer == " b"));
+    }
+
+    #[test]
+    fn doubled_bracket_after_a_style_is_literal() {
+        let literal = tokens("@(red)[[a] b");
+        assert!(matches!(&literal[..], [
+            FormatToken::StyleChange { style_specs },
+            FormatToken::Text { content },
+        ] if style_specs == &["red"] && content == "[a] b"));
+        let nested = tokens("@(red)[x @(bold)[[0]] y");
+        assert!(matches!(&nested[..], [
+            FormatToken::ScopeStart,
+            FormatToken::StyleChange { .. },
+            FormatToken::Text { content: before },
+            FormatToken::StyleChange { .. },
+            FormatToken::Text { content: inner },
+            FormatToken::ScopeEnd,
+            FormatToken::Text { content: after },
+        ] if before == "x " && inner == "[0]" && after == " y"));
+        assert!(matches!(&tokens("@()[[x]")[..], [FormatToken::StyleReset, FormatToken::Text { content }] if content == "[x]"));
+    }
+
+    #[test]
+    fn positional_and_named_arguments_are_bound() {
+        let (tokens, usage) = bind("{} {name} {}", 2, &["name", "other"]).unwrap();
+        let names: Vec<&str> = tokens.iter().filter_map(|t| match t {
+            FormatToken::Variable { name, .. } => Some(name.as_str()),
+            _ => None,
+        }).collect();
+        assert_eq!(names, ["__cio_arg_0", "name", "__cio_arg_1"]);
+        assert_eq!(usage.positional, [true, true]);
+        assert_eq!(usage.named, [true, false]);
+    }
+
+    #[test]
+    fn unused_and_missing_arguments_are_detected() {
+        let (_, usage) = bind("{1}", 2, &[]).unwrap();
+        assert_eq!(usage.positional, [false, true]);
+        assert_eq!(bind("{} {}", 1, &[]).err().unwrap(), "invalid reference to positional argument 1 (there is 1 argument)");
+        assert_eq!(bind("{3}", 0, &[]).err().unwrap(), "invalid reference to positional argument 3 (no arguments were given)");
+        assert_eq!(bind("{:>5$}", 2, &[]).err().unwrap(), "invalid reference to positional argument 5 (there are 2 arguments)");
+    }
+
+    #[test]
+    fn width_and_precision_arguments_are_rewritten() {
+        let (tokens, usage) = bind("{:1$.2$} {x:w$.p$}", 3, &["w", "p"]).unwrap();
+        let formats: Vec<&str> = tokens.iter().filter_map(|t| match t {
+            FormatToken::Variable { format, .. } => format.as_deref(),
+            _ => None,
+        }).collect();
+        assert_eq!(formats, ["__cio_arg_1$.__cio_arg_2$", "w$.p$"]);
+        assert_eq!(usage.positional, [true, true, true]);
+        assert_eq!(usage.named, [true, true]);
+    }
+}
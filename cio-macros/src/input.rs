@@ -0,0 +1,438 @@
+//! Procedural macro for type-safe user input with validation.
+//!
+//! This macro displays a prompt, reads user input from stdin, trims it,
+//! and attempts to parse it according to the expected return type.
+//! If the input is empty or cannot be parsed, it displays an error
+//! and prompts the user again. `try_input!` is the fallible variant: it
+//! reads a single line and returns `Result<T, cio::runtime::InputError>`.
+//!
+//! # Features
+//! - Automatically parses input to the target type
+//! - Displays colorized error messages on invalid input
+//! - Prevents empty input submission
+//! - Handles various primitive types with appropriate validation
+//! - Seamless integration with println! macro color syntax
+//! - Automatic retry loop until valid input is provided
+//!
+//! # Supported Types
+//! - All primitive numeric types (`i8`, `i16`, `i32`, `i64`, `i128`, `isize`,
+//!   `u8`, `u16`, `u32`, `u64`, `u128`, `usize`, `f32`, `f64`)
+//! - `String` - Returns the trimmed input without further parsing
+//! - `bool` - Accepts "true"/"false" (use `confirm!` for yes/no questions)
+//! - `char` - Accepts a single character input
+//! - Any other `FromStr` type (`NonZeroU32`, `IpAddr`, `Uuid`, ...)
+//! - `Option<T>` - An empty line yields `None` instead of an error
+//! - `Vec<T>` - Comma or whitespace separated elements (`1, 2, 3`)
+//! - Tuples of 2 to 6 elements - `(i32, f64)` from `3 4.5`
+//! - `HashMap<K, V>` / `BTreeMap<K, V>` - `key=value` entries (`a=1, b=2`)
+//!
+//! `Option`, `Vec`, tuple and map targets must have their type written where
+//! the macro is used (`let v: Vec<u32> = input!(...)`), because the macro
+//! picks the conversion from the target type.
+//!
+//! # Examples
+//!
+//! ## Basic Type-Safe Input
+//! let name: String = input!("Enter your name: ");
+//! let age: i32 = input!("Enter your age: ");
+//! let height: f64 = input!("Enter your height in meters: ");
+//! let proceed: bool = confirm!("Would you like to continue?");
+//! let favorite_letter: char = input!("What's your favorite letter? ");
+//!
+//! ## Colored Prompts
+//! let score: u32 = input!("@(green, bold)Enter score (0-100): @()");
+//! let username: String = input!("@(cyan)Username: @()");
+//! let password: String = input!("@(yellow, dimmed)Password: @()", secret);
+//!
+//! ## Advanced Usage
+//! let temperature: f32 = input!("@(blue)Temperature in °C: @()");
+//! let sure: bool = confirm!("@(red, bold)Are you sure?@()", default = false);
+//!
+//! # Error Handling
+//!
+//! The macro automatically handles parsing errors and empty input:
+//! - Empty input displays: "Error: Unauthorized empty input."
+//! - Invalid format displays: "Error: {parsing_error}."
+//! - Both errors are shown in red, bold, blinking text
+//! - User is automatically prompted again until valid input is provided
+//! - End of input (closed stdin, Ctrl-D) ends the line and exits with status 0
+//! - A read error is reported on stderr and exits with status 1
+//!
+//! ## Retry Policy
+//! let age: u8 = input!("Age: ", attempts = 3);                       // exit(1) after 3 errors
+//! let age: Result<u8, _> = try_input!("Age: ", attempts = 3);          // last error after 3
+//! let n: i32 = input!("N: ", error = "@(yellow)⚠ {e}@()", blink = false);
+//!
+//! `attempts`, `error` and `blink` override the global policy set with
+//! `cio::runtime::set_retry_policy`. Error templates use the style syntax
+//! with `{e}` for the error message.
+//!
+//! ## Validation
+//! let age: u8 = input!("Age: ", range = 0..=130);
+//! let name: String = input!("Name: ", validate = |s: &String| s.len() >= 3, msg = "Too short");
+//!
+//! `range` accepts any range expression over the target type. `validate`
+//! takes a closure receiving `&T`; when it returns `false`, `msg` (or
+//! "Invalid value") is shown and the user is asked again.
+//!
+//! ## Defaults
+//! let port: u16 = input!("Port: ", default = 8080); // shows "Port [8080]: "
+//! let name: String = input!("Name: ", default = "anon"); // converted with `Into`
+//! let nickname: Option<String> = input!("Nickname: "); // empty line → None
+//!
+//! ## Secret Input
+//! let password: String = input!("Password: ", secret);        // nothing echoed
+//! let pin: String = input!("PIN: ", secret = '*');              // one `*` per key
+//!
+//! Echo is disabled through termios on Unix and restored after the line is
+//! read, on panic and on Ctrl-C. Secrets keep leading/trailing spaces.
+//!
+//! ## Line Editing
+//! let cmd: String = input!("> ", history = ".calc_history", complete = |word: &str| complete_command(word));
+//!
+//! With the `line-editor` cargo feature, terminal input supports cursor
+//! movement, per-prompt history (Up/Down) and Tab completion. `history`
+//! persists the prompt's history to a file, and `complete` receives the word
+//! before the cursor and returns the candidates. Without the feature, both
+//! options are accepted and ignored.
+//!
+//! ## Fallible Input
+//! match try_input!("Age: ") {
+//!     Ok(age) => process(age),
+//!     Err(InputError::Eof) => return,
+//!     Err(InputError::Parse(msg)) => eprintln!("bad age: {msg}"),
+//!     Err(e) => return Err(e.into()),
+//! }
+//!
+//! # Technical Notes
+//!
+//! - Reading and conversion live in `cio::runtime` (`read_input_line`,
+//!   `InputTarget`), so EOF and I/O errors are never mistaken for empty input
+//! - Automatically trims whitespace from input
+//! - Leverages Rust's FromStr trait for type conversion
+//! - Prompts are rendered with `format!`, so the full color syntax is available,
+//!   and written through the input backend (`cio::runtime::set_input_backend`)
+//!   together with error messages, so tests can script answers and capture
+//!   the whole exchange
+//! - No heap allocations beyond the input string buffer
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, Ident, LitStr, Token};
+use crate::formatext;
+
+const INPUT_OPTIONS: [&str; 10] = [
+    "range", "validate", "msg", "default", "secret", "history", "complete", "attempts", "error", "blink",
+];
+const INPUT_FLAGS: [&str; 1] = ["secret"];
+
+pub struct InputArgs {
+    pub prompt: LitStr,
+    options: Vec<(Ident, Option<Expr>)>,
+}
+impl Parse for InputArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        InputArgs::parse_with(input, &INPUT_OPTIONS, &INPUT_FLAGS)
+    }
+}
+impl InputArgs {
+    pub fn parse_with(input: ParseStream, known: &[&str], flags: &[&str]) -> syn::Result<Self> {
+        let prompt = input.parse()?;
+        let mut options: Vec<(Ident, Option<Expr>)> = Vec::new();
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            for arg in Punctuated::<Expr, Token![,]>::parse_terminated(input)? {
+                let (left, value) = match arg {
+                    Expr::Assign(assign) => (*assign.left, Some(*assign.right)),
+                    flag @ Expr::Path(_) => (flag, None),
+                    other => return Err(syn::Error::new_spanned(other, "expected an option like `range = 1..=10`")),
+                };
+                let name = match &left {
+                    Expr::Path(path) if path.qself.is_none() => path.path.get_ident().cloned(),
+                    _ => None,
+                };
+                let Some(name) = name else {
+                    return Err(syn::Error::new_spanned(&left, "expected an option name"));
+                };
+                let key = name.to_string();
+                if !known.contains(&key.as_str()) {
+                    let message = match formatext::suggest(&key, known.iter().copied()) {
+                        Some(known) => format!("unknown input option `{}`, did you mean `{}`?", key, known),
+                        None => format!("unknown input option `{}` (expected one of {})", key,
+                                        known.iter().map(|o| format!("`{}`", o)).collect::<Vec<_>>().join(", ")),
+                    };
+                    return Err(syn::Error::new_spanned(&name, message));
+                }
+                if value.is_none() && !flags.contains(&key.as_str()) {
+                    return Err(syn::Error::new_spanned(&name, format!("input option `{}` expects a value: `{} = ...`", key, key)));
+                }
+                if options.iter().any(|(n, _)| n == &name) {
+                    return Err(syn::Error::new_spanned(&name, format!("duplicate input option `{}`", name)));
+                }
+                options.push((name, value));
+            }
+        }
+        Ok(InputArgs { prompt, options })
+    }
+    pub fn option(&self, key: &str) -> Option<&Expr> {
+        self.options.iter().find(|(name, _)| name == key).and_then(|(_, expr)| expr.as_ref())
+    }
+    fn has_flag(&self, key: &str) -> bool {
+        self.options.iter().any(|(name, _)| name == key)
+    }
+    fn option_name(&self, key: &str) -> Option<&Ident> {
+        self.options.iter().find(|(name, _)| name == key).map(|(name, _)| name)
+    }
+}
+fn prompt_with_default(prompt: &LitStr) -> LitStr {
+    let value = prompt.value();
+    let head = value.trim_end_matches(|c: char| c.is_whitespace() || ":?>".contains(c));
+    let tail = match &value[head.len()..] {
+        "" => " ",
+        tail => tail,
+    };
+    LitStr::new(&format!("{}@(dimmed)[ [{{__cio_default}}]]{}", head, tail), prompt.span())
+}
+struct InputCode {
+    setup: TokenStream2,
+    read_value: TokenStream2,
+    has_default: bool,
+}
+fn input_code(args: &InputArgs) -> syn::Result<InputCode> {
+    let prompt = &args.prompt;
+    if let Err(e) = formatext::parse_format_string(&prompt.value()) {
+        return Err(e.to_syn_error(prompt));
+    }
+    if let (Some(msg), None) = (args.option_name("msg"), args.option("validate")) {
+        return Err(syn::Error::new_spanned(msg, "`msg` is only used together with `validate`"));
+    }
+    let mut checks = Vec::new();
+    if let Some(range) = args.option("range") {
+        checks.push(quote! { .and_then(|value| ::cio::runtime::check_range(value, &(#range))) });
+    }
+    if let Some(validate) = args.option("validate") {
+        let msg = match args.option("msg") {
+            Some(msg) => quote! { #msg },
+            None => quote! { "Invalid value" },
+        };
+        checks.push(quote! { .and_then(|value| ::cio::runtime::check_input(value, #validate, #msg)) });
+    }
+    let target = quote! {
+        use ::cio::runtime::{ConvertedDefault as _, SameTypeDefault as _, ScalarTarget as _, StructuredTarget as _};
+        let __cio_target = ::cio::runtime::InputTarget::new();
+    };
+    let (setup, prompt) = match args.option("default") {
+        Some(default) => (quote! { #target let __cio_default = #default; }, prompt_with_default(prompt)),
+        None => (target, prompt.clone()),
+    };
+    if args.has_flag("secret") {
+        for key in ["history", "complete"] {
+            if let Some(name) = args.option_name(key) {
+                return Err(syn::Error::new_spanned(name, format!("`{}` cannot be used with `secret`", key)));
+            }
+        }
+    }
+    let mut line_options = Vec::new();
+    if let Some(history) = args.option("history") {
+        line_options.push(quote! { .history_file(#history) });
+    }
+    if let Some(complete) = args.option("complete") {
+        line_options.push(quote! { .completion(#complete) });
+    }
+    let read_line = match (args.has_flag("secret"), args.option("secret")) {
+        (true, Some(mask)) => quote! {
+            ::cio::runtime::write_output(&::cio::format!(#prompt))
+                .and_then(|()| ::cio::runtime::read_secret_line(Some(#mask)))
+        },
+        (true, None) => quote! {
+            ::cio::runtime::write_output(&::cio::format!(#prompt))
+                .and_then(|()| ::cio::runtime::read_secret_line(None))
+        },
+        (false, _) => quote! {
+            ::cio::runtime::read_prompted_line(&::cio::format!(#prompt), ::cio::runtime::LineOptions::new()#(#line_options)*)
+        },
+    };
+    Ok(InputCode {
+        setup,
+        read_value: quote! {
+            #read_line
+                .and_then(|line| (&__cio_target).parse_answer(&line))
+                #(#checks)*
+        },
+        has_default: args.option("default").is_some(),
+    })
+}
+fn check_error_template(template: &Expr) -> syn::Result<()> {
+    let Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit), .. }) = template else {
+        return Ok(());
+    };
+    let (tokens, _) = formatext::parse_format_string(&lit.value()).map_err(|e| e.to_syn_error(lit))?;
+    for token in tokens {
+        match token {
+            formatext::FormatToken::Variable { name, format: None, .. } if name == "e" => {},
+            formatext::FormatToken::Variable { .. } | formatext::FormatToken::StyleVariable { .. } =>
+                return Err(syn::Error::new_spanned(lit, "error templates only accept the `{e}` placeholder")),
+            _ => {},
+        }
+    }
+    Ok(())
+}
+fn retry_policy(args: &InputArgs) -> syn::Result<TokenStream2> {
+    let mut settings = Vec::new();
+    if let Some(attempts) = args.option("attempts") {
+        if let Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(count), .. }) = attempts
+            && count.base10_parse::<usize>().is_ok_and(|count| count == 0) {
+            return Err(syn::Error::new_spanned(attempts, "`attempts` must be at least 1"));
+        }
+        settings.push(quote! { .max_attempts(#attempts) });
+    }
+    if let Some(template) = args.option("error") {
+        check_error_template(template)?;
+        settings.push(quote! { .error_template(#template) });
+    }
+    if let Some(blink) = args.option("blink") {
+        settings.push(quote! { .blink(#blink) });
+    }
+    Ok(quote! { ::cio::runtime::retry_policy()#(#settings)* })
+}
+fn exit_when_exhausted() -> TokenStream2 {
+    quote! {
+        __cio_policy.report_exhausted();
+        ::std::process::exit(1);
+    }
+}
+pub fn retry_loop(read_value: TokenStream2, extra_arms: TokenStream2) -> TokenStream2 {
+    retry_loop_with(quote! {}, read_value, extra_arms, quote! { ::cio::runtime::retry_policy() }, exit_when_exhausted())
+}
+fn retry_loop_with(
+    prelude: TokenStream2, read_value: TokenStream2, extra_arms: TokenStream2, policy: TokenStream2, exhausted: TokenStream2,
+) -> TokenStream2 {
+    quote! {{
+        let __cio_policy = #policy;
+        let mut __cio_failures = 0usize;
+        loop {
+            #prelude
+            let __cio_value = { #read_value };
+            match __cio_value {
+                Ok(value) => break value,
+                #extra_arms
+                Err(::cio::runtime::InputError::Eof) => {
+                    let _ = ::cio::runtime::write_output("\n");
+                    ::std::process::exit(0);
+                },
+                Err(e @ ::cio::runtime::InputError::Io(_)) => {
+                    ::cio::eprintln!("@(red, bold)Error: {e}.@()");
+                    ::std::process::exit(1);
+                },
+                Err(e) => {
+                    // A failed write shows up again, as an `Io` error, at the next prompt.
+                    let _ = __cio_policy.report(&e);
+                    __cio_failures += 1;
+                    if __cio_policy.is_exhausted(__cio_failures) {
+                        #exhausted
+                    }
+                },
+            }
+        }
+    }}
+}
+pub fn input_impl(input: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(input as InputArgs);
+    let (InputCode { setup, read_value, has_default }, policy) = match input_code(&args).and_then(|code| retry_policy(&args).map(|policy| (code, policy))) {
+        Ok(code) => code,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let default_arm = if has_default {
+        quote! { Err(::cio::runtime::InputError::Empty) => break (&__cio_target.default_value(__cio_default)).take_default(), }
+    } else {
+        quote! {}
+    };
+    // Never runs: gives the loop the caller's type before the target picks its conversion.
+    let pin_type = quote! { if false { break __cio_target.placeholder(); } };
+    let retry = retry_loop_with(pin_type, read_value, default_arm, policy, exit_when_exhausted());
+    quote! {{
+        #setup
+        #retry
+    }}.into()
+}
+pub fn try_input_impl(input: TokenStream) -> TokenStream {
+    let args = syn::parse_macro_input!(input as InputArgs);
+    let (InputCode { setup, read_value, has_default }, policy) = match input_code(&args).and_then(|code| retry_policy(&args).map(|policy| (code, policy))) {
+        Ok(code) => code,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    if args.option("attempts").is_none() {
+        for key in ["error", "blink"] {
+            if let Some(name) = args.option_name(key) {
+                let message = format!("`{}` only applies when `try_input!` retries: add `attempts = n`", key);
+                return syn::Error::new_spanned(name, message).to_compile_error().into();
+            }
+        }
+    } else {
+        let default_arm = if has_default {
+            quote! { Err(::cio::runtime::InputError::Empty) => break Ok((&__cio_target.default_value(__cio_default)).take_default()), }
+        } else {
+            quote! {}
+        };
+        let arms = quote! {
+            #default_arm
+            Err(e @ (::cio::runtime::InputError::Eof | ::cio::runtime::InputError::Io(_))) => break Err(e),
+        };
+        let read_value = quote! { { #read_value }.map(::std::result::Result::Ok) };
+        let pin_type = quote! { if false { break Ok(__cio_target.placeholder()); } };
+        let retry = retry_loop_with(pin_type, read_value, arms, policy, quote! { break Err(e); });
+        return quote! {{
+            #setup
+            #retry
+        }}.into();
+    }
+    let value = if has_default {
+        quote! {
+            match { #read_value } {
+                Err(::cio::runtime::InputError::Empty) => Ok((&__cio_target.default_value(__cio_default)).take_default()),
+                other => other,
+            }
+        }
+    } else {
+        quote! { #read_value }
+    };
+    quote! {{
+        #setup
+        '__cio_input: {
+            if false {
+                break '__cio_input Ok(__cio_target.placeholder());
+            }
+            #value
+        }
+    }}.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template_error(template: &str) -> Option<String> {
+        let expr: Expr = syn::parse_str(template).expect("template is an expression");
+        check_error_template(&expr).err().map(|e| e.to_string())
+    }
+
+    #[test]
+    fn literal_error_templates_are_checked() {
+        assert_eq!(template_error(r#""@(yellow)[{e}], try again""#), None);
+        assert!(template_error(r#""@(red, sparkly){e}""#).unwrap().starts_with("unknown color or style `sparkly`"));
+        assert_eq!(template_error(r#""{e} {x}""#).unwrap(), "error templates only accept the `{e}` placeholder");
+        assert!(template_error(r#""@(red)[{e}""#).unwrap().starts_with("unclosed style scope"));
+        assert_eq!(template_error("my_template()"), None);
+    }
+
+    #[test]
+    fn zero_attempts_are_rejected() {
+        let args: InputArgs = syn::parse_str(r#""Age: ", attempts = 0"#).unwrap();
+        assert_eq!(retry_policy(&args).unwrap_err().to_string(), "`attempts` must be at least 1");
+        let args: InputArgs = syn::parse_str(r#""Age: ", attempts = 1"#).unwrap();
+        assert!(retry_policy(&args).is_ok());
+    }
+}
@@ -0,0 +1,78 @@
+//! Procedural macros for the CIO crate.
+//!
+//! This crate implements the `println!` family (`print!`, `eprintln!`,
+//! `eprint!`, `format!`, `write!`, `writeln!`), the `input!`/`try_input!`
+//! macros, the `select!`/`multiselect!` menus and `confirm!`. It is not meant
+//! to be used directly: depend on `cio`, which re-exports these macros
+//! together with the `cio-runtime` support library the generated code calls
+//! into.
+
+use proc_macro::TokenStream;
+use println::OutputTarget;
+
+mod colorstyle;
+mod confirm;
+mod formatext;
+mod println;
+mod input;
+mod select;
+
+#[proc_macro]
+pub fn println(input: TokenStream) -> TokenStream {
+    println::println_impl(input, OutputTarget::Stdout, true)
+}
+
+#[proc_macro]
+pub fn print(input: TokenStream) -> TokenStream {
+    println::println_impl(input, OutputTarget::Stdout, false)
+}
+
+#[proc_macro]
+pub fn eprintln(input: TokenStream) -> TokenStream {
+    println::println_impl(input, OutputTarget::Stderr, true)
+}
+
+#[proc_macro]
+pub fn eprint(input: TokenStream) -> TokenStream {
+    println::println_impl(input, OutputTarget::Stderr, false)
+}
+
+#[proc_macro]
+pub fn format(input: TokenStream) -> TokenStream {
+    println::println_impl(input, OutputTarget::String, false)
+}
+
+#[proc_macro]
+pub fn write(input: TokenStream) -> TokenStream {
+    println::println_impl(input, OutputTarget::Writer, false)
+}
+
+#[proc_macro]
+pub fn writeln(input: TokenStream) -> TokenStream {
+    println::println_impl(input, OutputTarget::Writer, true)
+}
+
+#[proc_macro]
+pub fn input(input: TokenStream) -> TokenStream {
+    input::input_impl(input)
+}
+
+#[proc_macro]
+pub fn try_input(input: TokenStream) -> TokenStream {
+    input::try_input_impl(input)
+}
+
+#[proc_macro]
+pub fn select(input: TokenStream) -> TokenStream {
+    select::select_impl(input, false)
+}
+
+#[proc_macro]
+pub fn multiselect(input: TokenStream) -> TokenStream {
+    select::select_impl(input, true)
+}
+
+#[proc_macro]
+pub fn confirm(input: TokenStream) -> TokenStream {
+    confirm::confirm_impl(input)
+}
@@ -0,0 +1,287 @@
+//! Advanced procedural macro for console output with rich formatting capabilities.
+//!
+//! This macro extends the standard `println!` with:
+//! - ANSI color and style formatting using @(...) syntax
+//! - Matrix and container pretty-printing with specialized formats
+//! - Dynamic separators using $(...) syntax
+//! - Rich expression evaluation in format strings
+//! - Positional (`{}`, `{0}`) and named (`name = expr`) arguments as in std
+//!
+//! The same expansion backs the whole family; the macros only differ in
+//! where the rendered string goes:
+//! - `println!` / `print!` - standard output (flushed)
+//! - `eprintln!` / `eprint!` - standard error
+//! - `format!` - returns the `String`
+//! - `writeln!` / `write!` - any `std::fmt::Write` or `std::io::Write` sink,
+//!   returning the sink's `Result` like the std macros
+//!
+//! # Format Specifiers
+//! - `:a` - Array format with proper indentation for nested structures
+//! - `:c` - Compact single-line format for any data structure
+//! - `:j` - JSON-like pretty format for complex structures
+//! - `:m` - Matrix format with proper borders for 2D arrays
+//! - `:d` - Determinant format with vertical bars
+//! - `:t` - Table format with borders and optional column headers
+//!
+//! # Style Syntax
+//! - Basic: `@(red, bold)Hello @(blue)World@()`
+//! - Dynamic: `@(color_var)Text@()` where color_var is a variable containing style names
+//! - Reset: `@()` resets to default style (or to the enclosing scope's style)
+//! - Scoped: `@(bold)Total: @(red)[42] units` - inner styles inherit the outer
+//!   ones and the outer style is restored after `]`
+//!
+//! Escape sequences are stripped at runtime when colors are disabled for the
+//! destination stream (`NO_COLOR`, `TERM=dumb`, output not a terminal, or
+//! `cio::runtime::set_color_mode(ColorMode::Never)`).
+//!
+//! # Examples
+//!
+//! ## Basic Color Formatting
+//! println!("@(red, bold)Error:@() Something went wrong");
+//! println!("@(green)Success!@() Operation completed");
+//!
+//! ## Table Formatting
+//! use serde_json::json;
+//! let data = json!({"France": "Paris", "Germany": "Berlin"});
+//! println!("Countries: {data:t(Country, Capital)}");
+//!
+//! ## Dynamic Separators
+//! println!("Loading$(...)"); // No newline, useful for progress indicators
+//! println!("Status$( - )"); // Custom separator
+//!
+//! ## Other Destinations
+//! eprintln!("@(yellow, bold)warning:@() {msg}");
+//! let cell = format!("@(green)OK@() {count}");
+//! writeln!(out, "{report:t}")?;
+//!
+//! ## Escapes
+//! println!("{{\"id\": {id}}}"); // {"id": 7}
+//! println!("echo $$(date) @@(home)"); // literal $( and @(, no separator
+//!
+//! ## Format Arguments
+//! println!("{}: {x:t}", title, x = data);
+//! println!("{0} scored {1:.1} ({0})", name, score);
+//! println!("@({})Alert@() {}", "red, bold", message);
+//!
+//! ## Advanced Formatting
+//! let matrix = vec![vec![1, 2], vec![3, 4]];
+//! println!("Matrix data: {matrix:m}");
+//! println!("Compact: {matrix:c}");
+//!
+//! # Color Palette
+//!
+//! **Standard Colors**: black, red, green, yellow, blue, magenta, cyan, white
+//! **Bright Colors**: bright_red, bright_green, bright_blue, etc.
+//! **Styles**: bold, italic, underline, dimmed, blink, reversed, hidden, strikethrough
+//!
+//! # Technical Notes
+//!
+//! - Automatically handles JSON serialization for complex data structures
+//! - Preserves original variable references to avoid unused variable warnings
+//! - Supports nested format specifiers and dynamic style variables
+//! - Cross-platform ANSI color support with graceful fallback
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{LitStr, Token, punctuated::Punctuated, Expr, Ident, parse::{Parse, ParseStream, Parser}};
+use regex::Regex;
+use crate::colorstyle;
+use crate::formatext;
+
+#[derive(Clone, Copy)]
+pub enum OutputTarget {
+    Stdout,
+    Stderr,
+    String,
+    Writer,
+}
+impl OutputTarget {
+    fn macro_name(&self, newline: bool) -> &'static str {
+        match (self, newline) {
+            (OutputTarget::Stdout, false) => "print",
+            (OutputTarget::Stdout, true) => "println",
+            (OutputTarget::Stderr, false) => "eprint",
+            (OutputTarget::Stderr, true) => "eprintln",
+            (OutputTarget::String, _) => "format",
+            (OutputTarget::Writer, false) => "write",
+            (OutputTarget::Writer, true) => "writeln",
+        }
+    }
+}
+pub struct PrintlnInput {
+    destination: Option<Expr>,
+    format_string: LitStr,
+    args: Punctuated<Expr, Token![,]>,
+}
+struct FormatArguments {
+    positional: Vec<Expr>,
+    named: Vec<(Ident, Expr)>,
+}
+fn split_arguments(args: Punctuated<Expr, Token![,]>) -> syn::Result<FormatArguments> {
+    let mut positional = Vec::new();
+    let mut named: Vec<(Ident, Expr)> = Vec::new();
+    for arg in args {
+        match arg {
+            Expr::Assign(assign) => {
+                let name = match &*assign.left {
+                    Expr::Path(path) if path.qself.is_none() => path.path.get_ident().cloned(),
+                    _ => None,
+                };
+                let Some(name) = name else {
+                    return Err(syn::Error::new_spanned(&assign.left, "expected an identifier for the named argument"));
+                };
+                if named.iter().any(|(n, _)| n == &name) {
+                    return Err(syn::Error::new_spanned(&name, format!("duplicate argument named `{}`", name)));
+                }
+                named.push((name, *assign.right));
+            },
+            expr => {
+                if !named.is_empty() {
+                    return Err(syn::Error::new_spanned(&expr, "positional arguments cannot follow named arguments"));
+                }
+                positional.push(expr);
+            },
+        }
+    }
+    Ok(FormatArguments { positional, named })
+}
+impl PrintlnInput {
+    fn parse_with_destination(input: ParseStream, has_destination: bool) -> syn::Result<Self> {
+        let destination = if has_destination {
+            let destination = input.parse()?;
+            input.parse::<Token![,]>()?;
+            Some(destination)
+        } else {
+            None
+        };
+        let format_string = input.parse()?;
+        let mut args = Punctuated::new();
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            args = Punctuated::<Expr, Token![,]>::parse_terminated(input)?;
+        }
+        Ok(PrintlnInput { destination, format_string, args })
+    }
+}
+impl Parse for PrintlnInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Self::parse_with_destination(input, false)
+    }
+}
+pub fn println_impl(input: TokenStream, target: OutputTarget, newline: bool) -> TokenStream {
+    let has_destination = matches!(target, OutputTarget::Writer);
+    let parser = |input: ParseStream| PrintlnInput::parse_with_destination(input, has_destination);
+    let PrintlnInput { destination, format_string, args } = match parser.parse(input) {
+        Ok(parsed) => parsed,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let FormatArguments { positional, named } = match split_arguments(args) {
+        Ok(arguments) => arguments,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let mut fmt_str = format_string.value();
+    let sep_pattern = Regex::new(r"\$\(([^)]*)\)$").unwrap();
+    let sep_match = sep_pattern.captures(&fmt_str)
+        .filter(|caps| !fmt_str[..caps.get(0).unwrap().start()].ends_with('$'))
+        .map(|caps| (caps.get(0).unwrap().start(), caps[1].to_string()));
+    let sep_content = sep_match.as_ref().map(|(_, sep)| sep.clone());
+    let is_input_call = sep_content.as_ref().is_some_and(|s| s == "\"\"");
+    if let Some((start, _)) = sep_match {
+        fmt_str.truncate(start);
+    }
+    let (mut tokens, mut used_vars) = match formatext::parse_format_string(&fmt_str) {
+        Ok(parsed) => parsed,
+        Err(e) => return e.to_syn_error(&format_string).to_compile_error().into(),
+    };
+    let named_names: Vec<String> = named.iter().map(|(n, _)| n.to_string()).collect();
+    let mut usage = match formatext::bind_arguments(&mut tokens, &mut used_vars, positional.len(), &named_names) {
+        Ok(usage) => usage,
+        Err(msg) => return syn::Error::new(format_string.span(), msg).to_compile_error().into(),
+    };
+    if let Some(i) = sep_content.as_ref().and_then(|sep| named_names.iter().position(|n| n == sep)) {
+        usage.named[i] = true;
+    }
+    let mut unused = positional.iter().zip(&usage.positional)
+        .filter(|(_, used)| !**used)
+        .map(|(expr, _)| syn::Error::new_spanned(expr, "argument never used"))
+        .chain(named.iter().zip(&usage.named)
+            .filter(|(_, used)| !**used)
+            .map(|((name, _), _)| syn::Error::new_spanned(name, "named argument never used")));
+    if let Some(mut error) = unused.next() {
+        for e in unused { error.combine(e); }
+        return error.to_compile_error().into();
+    }
+    let mut segments = formatext::generate_output_code(&tokens, matches!(target, OutputTarget::Stderr));
+    match sep_content {
+        Some(_) if is_input_call => {},
+        Some(sep_var) => {
+            let is_valid_ident = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap().is_match(&sep_var);
+            segments.push(if is_valid_ident {
+                format!("__cio_result.push_str(&::std::format!(\"{{}}\", {}));", sep_var)
+            } else {
+                format!("__cio_result.push_str(\"{}\");", colorstyle::escape_string(&sep_var))
+            });
+        },
+        None if newline => segments.push("__cio_result.push('\\n');".to_string()),
+        None => {},
+    }
+    let mut suppress_warnings = Vec::new();
+    for var in used_vars {
+        suppress_warnings.push(format!("let _ = &{};", var));
+    }
+    let suppressions = suppress_warnings.join(" ");
+    let segments_code = segments.join("\n            ");
+    let final_code = format!(
+        r#"{{
+        {suppressions}
+        let mut __cio_result = ::std::string::String::new();
+        {segments}
+        __cio_result
+    }}"#,
+        suppressions = suppressions,
+        segments = segments_code
+    );
+    use syn::parse_str;
+    let generated_code = match parse_str::<Expr>(&final_code) {
+        Ok(code) => code,
+        Err(e) => {
+            let err_msg = format!("Error parsing in {}: {}", target.macro_name(newline), e);
+            let err_tokens = quote! { compile_error!(#err_msg) };
+            return TokenStream::from(err_tokens);
+        }
+    };
+    let bindings: Vec<Ident> = (0..positional.len())
+        .map(|i| format_ident!("{}", formatext::positional_binding(i)))
+        .chain(named.iter().map(|(name, _)| name.clone()))
+        .collect();
+    let values = positional.iter().chain(named.iter().map(|(_, expr)| expr));
+    let argument_bindings = if bindings.is_empty() {
+        quote! {}
+    } else {
+        quote! { let (#(#bindings,)*) = (#(&(#values),)*); }
+    };
+    let emit = match (target, destination) {
+        (OutputTarget::Stdout, _) => quote! {
+            ::std::print!("{}", __cio_result);
+            ::std::io::Write::flush(&mut ::std::io::stdout()).expect("Failed to flush stdout");
+        },
+        (OutputTarget::Stderr, _) => quote! { ::std::eprint!("{}", __cio_result); },
+        (OutputTarget::String, _) => quote! { __cio_result },
+        (OutputTarget::Writer, destination) => quote! {
+            (#destination).write_fmt(::std::format_args!("{}", __cio_result))
+        },
+    };
+    let stream = match target {
+        OutputTarget::Stderr => quote! { ::cio::runtime::OutputStream::Stderr },
+        _ => quote! { ::cio::runtime::OutputStream::Stdout },
+    };
+    let result = quote! {
+        {
+            #argument_bindings
+            let __cio_result: ::std::string::String =
+                ::cio::runtime::apply_color_mode(#generated_code, #stream);
+            #emit
+        }
+    };
+    TokenStream::from(result)
+}
\ No newline at end of file
@@ -0,0 +1,97 @@
+//! Procedural macros for picking options from a numbered list.
+//!
+//! `select!` shows a prompt followed by the `Display` text of every option,
+//! numbered from 1, and asks for a choice until a valid one is given. It
+//! returns `(index, item)`, where `index` is zero-based and `item` is taken
+//! from the options by value. `multiselect!` accepts several choices at once
+//! and returns a `Vec<(index, item)>` in list order.
+//!
+//! # Accepted Answers
+//! - A number: `2`
+//! - A unique, case-insensitive prefix of the option text: `ban`
+//! - For `multiselect!`, a comma-separated mix including ranges: `1,3,5-7`
+//!
+//! # Examples
+//!
+//! ## Single Choice
+//! let fruits = ["Apple", "Banana", "Cherry"];
+//! let (index, fruit) = select!("@(green, bold)Pick a fruit:", fruits);
+//!
+//! ## Multiple Choices
+//! let toppings = vec![String::from("Cheese"), String::from("Ham"), String::from("Olives")];
+//! let chosen = multiselect!("Toppings:", &toppings); // Vec<(usize, &String)>
+//!
+//! # Technical Notes
+//!
+//! - Options can be any `IntoIterator` whose items implement `Display`
+//! - Rendering and answer parsing live in `cio::runtime` (`render_choices`,
+//!   `parse_choice`, `parse_choices`)
+//! - Invalid answers, EOF and I/O errors are handled exactly as in `input!`
+//! - An empty option list panics, since no answer could ever be valid
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, LitStr, Token};
+use crate::input;
+
+struct SelectArgs {
+    prompt: LitStr,
+    options: Expr,
+}
+impl Parse for SelectArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let prompt = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let options = input.parse()?;
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+        Ok(SelectArgs { prompt, options })
+    }
+}
+pub fn select_impl(input: TokenStream, multiple: bool) -> TokenStream {
+    let SelectArgs { prompt, options } = syn::parse_macro_input!(input as SelectArgs);
+    let (choice_prompt, parse, result) = if multiple {
+        (
+            "@(dimmed)Choices (e.g. 1,3,5-7):@() ",
+            quote! { ::cio::runtime::parse_choices(&line, &__cio_labels) },
+            quote! {
+                __cio_items.into_iter().enumerate()
+                    .filter(|(index, _)| __cio_choice.contains(index))
+                    .collect::<::std::vec::Vec<_>>()
+            },
+        )
+    } else {
+        (
+            "@(dimmed)Choice [1-{__cio_count}]:@() ",
+            quote! { ::cio::runtime::parse_choice(&line, &__cio_labels) },
+            quote! {
+                match __cio_items.into_iter().nth(__cio_choice) {
+                    Some(item) => (__cio_choice, item),
+                    None => unreachable!(),
+                }
+            },
+        )
+    };
+    let read_choice = input::retry_loop(quote! {
+        ::std::option::Option::take(&mut __cio_header)
+            .map_or(::std::result::Result::Ok(()), |header| ::cio::runtime::write_output(&header))
+            .and_then(|()| ::cio::runtime::write_output(&::cio::format!(#choice_prompt)))
+            .and_then(|()| ::cio::runtime::read_input_line())
+            .and_then(|line| #parse)
+    }, quote! {});
+    quote! {{
+        let __cio_items: ::std::vec::Vec<_> = ::std::iter::IntoIterator::into_iter(#options).collect();
+        let __cio_labels: ::std::vec::Vec<::std::string::String> = __cio_items.iter()
+            .map(::std::string::ToString::to_string)
+            .collect();
+        assert!(!__cio_labels.is_empty(), "select! needs at least one option");
+        let __cio_count = __cio_labels.len();
+        let mut __cio_header = ::std::option::Option::Some(
+            ::cio::format!(#prompt) + &::cio::format!("\n{}", ::cio::runtime::render_choices(&__cio_labels)),
+        );
+        let __cio_choice = #read_choice;
+        #result
+    }}.into()
+}
@@ -0,0 +1,306 @@
+//! Pluggable input/output backend for the interactive macros.
+//!
+//! `input!`, `try_input!`, `select!`, `multiselect!` and `confirm!` never
+//! touch stdin/stdout directly: prompts, error messages and answers all go
+//! through the backend selected for the current thread. This makes prompts
+//! testable and lets CI runs answer them without a terminal.
+//!
+//! # Backends
+//!
+//! - `Terminal` - stdin/stdout (default)
+//! - `Scripted` - canned answers and an in-memory transcript, for tests
+//! - Answers from the environment, for non-interactive runs (see below)
+//! - Any type implementing `InputBackend`
+//!
+//! # Selection
+//!
+//! The backend used by a prompt is, first match wins:
+//! 1. The thread-local backend installed with `set_input_backend` or
+//!    `with_input_backend`
+//! 2. `CIO_ANSWERS` - answers separated by newlines
+//! 3. `CIO_ANSWERS_FILE` - path of a file with one answer per line
+//! 4. `Terminal`
+//!
+//! Environment answers are shared by all threads and consumed in order;
+//! once they run out, prompts see end of input. If `CIO_ANSWERS_FILE`
+//! cannot be read, every read fails with that I/O error (`InputError::Io`).
+//!
+//! # Transcripts
+//!
+//! Non-interactive backends echo each answer after its prompt, followed by a
+//! newline, so logs and captured transcripts read like a terminal session.
+//! Secret answers are never echoed. Nothing is echoed at end of input, where
+//! `input!` ends the prompt line itself.
+//!
+//! # Errors and Nesting
+//!
+//! `write_output` returns a failed write as `InputError::Io`, so the macros
+//! handle it like a failed read. While a backend runs it is taken out of its
+//! thread slot, so a backend may prompt through the macros itself: those
+//! nested prompts use a backend installed with `with_input_backend`, the
+//! environment answers or the terminal.
+//!
+//! # Example
+//!
+//! ```text
+//! let script = Scripted::new(["alice", "42"]);
+//! let (name, age): (String, u32) = with_input_backend(script.clone(), || {
+//!     (input!("Name: "), input!("Age: "))
+//! });
+//! assert_eq!(script.transcript(), "Name: alice\nAge: 42\n");
+//! ```
+
+use crate::input::InputError;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+use std::rc::Rc;
+use std::sync::{Mutex, OnceLock};
+
+pub trait InputBackend {
+    fn read_line(&mut self) -> io::Result<Option<String>>;
+    fn write(&mut self, text: &str) -> io::Result<()>;
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+pub struct Terminal;
+impl InputBackend for Terminal {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        match io::stdin().lock().read_line(&mut line)? {
+            0 => Ok(None),
+            _ => Ok(Some(line.trim_end_matches(['\n', '\r']).to_string())),
+        }
+    }
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(text.as_bytes())?;
+        stdout.flush()
+    }
+    fn is_interactive(&self) -> bool {
+        true
+    }
+}
+#[derive(Default)]
+struct ScriptState {
+    answers: VecDeque<String>,
+    transcript: String,
+}
+#[derive(Clone, Default)]
+pub struct Scripted {
+    state: Rc<RefCell<ScriptState>>,
+}
+impl Scripted {
+    pub fn new<I, S>(answers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let state = ScriptState { answers: answers.into_iter().map(Into::into).collect(), transcript: String::new() };
+        Scripted { state: Rc::new(RefCell::new(state)) }
+    }
+    pub fn push_answer(&self, answer: impl Into<String>) {
+        self.state.borrow_mut().answers.push_back(answer.into());
+    }
+    pub fn remaining(&self) -> usize {
+        self.state.borrow().answers.len()
+    }
+    pub fn transcript(&self) -> String {
+        self.state.borrow().transcript.clone()
+    }
+}
+impl InputBackend for Scripted {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        Ok(self.state.borrow_mut().answers.pop_front())
+    }
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        self.state.borrow_mut().transcript.push_str(text);
+        Ok(())
+    }
+}
+struct EnvAnswers;
+type AnswerQueue = Result<Mutex<VecDeque<String>>, (io::ErrorKind, String)>;
+static ENV_ANSWERS: OnceLock<Option<AnswerQueue>> = OnceLock::new();
+fn env_answers() -> Option<&'static AnswerQueue> {
+    ENV_ANSWERS.get_or_init(|| {
+        let text = match (std::env::var("CIO_ANSWERS"), std::env::var_os("CIO_ANSWERS_FILE")) {
+            (Ok(answers), _) => answers,
+            (Err(_), Some(path)) => match std::fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(e) => {
+                    return Some(Err((e.kind(), format!("cannot read CIO_ANSWERS_FILE `{}`: {}", path.to_string_lossy(), e))));
+                },
+            },
+            (Err(_), None) => return None,
+        };
+        Some(Ok(Mutex::new(text.lines().map(|line| line.trim_end_matches('\r').to_string()).collect())))
+    }).as_ref()
+}
+impl InputBackend for EnvAnswers {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        match env_answers().expect("environment answers are configured") {
+            Ok(answers) => Ok(answers.lock().unwrap_or_else(|e| e.into_inner()).pop_front()),
+            Err((kind, message)) => Err(io::Error::new(*kind, message.clone())),
+        }
+    }
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        Terminal.write(text)
+    }
+}
+thread_local! {
+    static BACKEND: RefCell<Option<Box<dyn InputBackend>>> = RefCell::new(None);
+}
+pub fn set_input_backend(backend: impl InputBackend + 'static) -> Option<Box<dyn InputBackend>> {
+    BACKEND.with(|b| b.borrow_mut().replace(Box::new(backend)))
+}
+pub fn reset_input_backend() -> Option<Box<dyn InputBackend>> {
+    BACKEND.with(|b| b.borrow_mut().take())
+}
+pub fn with_input_backend<R>(backend: impl InputBackend + 'static, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Option<Box<dyn InputBackend>>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            if let Some(previous) = self.0.take() {
+                BACKEND.with(|b| *b.borrow_mut() = previous);
+            }
+        }
+    }
+    let _restore = Restore(Some(set_input_backend(backend)));
+    f()
+}
+pub(crate) fn with_backend<R>(f: impl FnOnce(&mut dyn InputBackend) -> R) -> R {
+    struct PutBack(Option<Box<dyn InputBackend>>);
+    impl Drop for PutBack {
+        fn drop(&mut self) {
+            if let Some(backend) = self.0.take() {
+                BACKEND.with(|b| {
+                    let mut slot = b.borrow_mut();
+                    if slot.is_none() {
+                        *slot = Some(backend);
+                    }
+                });
+            }
+        }
+    }
+    let mut taken = PutBack(BACKEND.with(|b| b.borrow_mut().take()));
+    match taken.0.as_deref_mut() {
+        Some(backend) => f(backend),
+        None if env_answers().is_some() => f(&mut EnvAnswers),
+        None => f(&mut Terminal),
+    }
+}
+#[cfg(feature = "line-editor")]
+pub(crate) fn terminal_backend_active() -> bool {
+    BACKEND.with(|b| b.borrow().is_none()) && env_answers().is_none()
+}
+pub(crate) fn backend_is_interactive() -> bool {
+    with_backend(|backend| backend.is_interactive())
+}
+pub fn write_output(text: &str) -> Result<(), InputError> {
+    with_backend(|backend| backend.write(text)).map_err(InputError::Io)
+}
+pub(crate) fn read_backend_line(echo: bool) -> io::Result<Option<String>> {
+    with_backend(|backend| {
+        let line = backend.read_line()?;
+        if let (Some(answer), false) = (&line, backend.is_interactive()) {
+            let shown = if echo { answer.as_str() } else { "" };
+            backend.write(&format!("{}\n", shown))?;
+        }
+        Ok(line)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{parse_input, read_input_line};
+
+    fn ask_number(prompt: &str) -> Result<u32, InputError> {
+        loop {
+            write_output(prompt)?;
+            match read_input_line().and_then(|line| parse_input(&line)) {
+                Err(e @ (InputError::Empty | InputError::Parse(_))) => write_output(&format!("Error: {}\n", e))?,
+                other => return other,
+            }
+        }
+    }
+    struct Broken;
+    impl InputBackend for Broken {
+        fn read_line(&mut self) -> io::Result<Option<String>> {
+            Ok(Some("1".to_string()))
+        }
+        fn write(&mut self, _: &str) -> io::Result<()> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+    }
+    struct Nested(Scripted);
+    impl InputBackend for Nested {
+        fn read_line(&mut self) -> io::Result<Option<String>> {
+            let inner = Scripted::new(["5"]);
+            let answer = with_input_backend(inner.clone(), || ask_number("Inner: "));
+            self.0.write(&inner.transcript())?;
+            Ok(answer.ok().map(|n| (n * 2).to_string()))
+        }
+        fn write(&mut self, text: &str) -> io::Result<()> {
+            self.0.write(text)
+        }
+    }
+
+    #[test]
+    fn scripted_answers_are_echoed_in_the_transcript() {
+        let script = Scripted::new(["alice", "42"]);
+        let (name, age) = with_input_backend(script.clone(), || {
+            write_output("Name: ").unwrap();
+            let name = read_input_line().unwrap();
+            (name, ask_number("Age: ").unwrap())
+        });
+        assert_eq!((name.as_str(), age), ("alice", 42));
+        assert_eq!(script.transcript(), "Name: alice\nAge: 42\n");
+        assert_eq!(script.remaining(), 0);
+    }
+
+    #[test]
+    fn scripted_parse_error_asks_again() {
+        let script = Scripted::new(["forty", "", "40"]);
+        let age = with_input_backend(script.clone(), || ask_number("Age: "));
+        assert_eq!(age.unwrap(), 40);
+        assert_eq!(
+            script.transcript(),
+            "Age: forty\nError: invalid digit found in string\nAge: \nError: Unauthorized empty input\nAge: 40\n",
+        );
+    }
+
+    #[test]
+    fn scripted_end_of_input_is_eof_without_echo() {
+        let script = Scripted::new(["7"]);
+        let (first, second) = with_input_backend(script.clone(), || (ask_number("A: "), ask_number("B: ")));
+        assert_eq!(first.unwrap(), 7);
+        assert!(matches!(second, Err(InputError::Eof)));
+        assert_eq!(script.transcript(), "A: 7\nB: ");
+    }
+
+    #[test]
+    fn backend_is_restored_after_scope() {
+        let outer = Scripted::new(["outer"]);
+        with_input_backend(outer.clone(), || {
+            with_input_backend(Scripted::new(["inner"]), || write_output("inner")).unwrap();
+            write_output("outer").unwrap();
+        });
+        assert_eq!(outer.transcript(), "outer");
+    }
+
+    #[test]
+    fn write_failures_are_io_errors() {
+        let result = with_input_backend(Broken, || ask_number("Age: "));
+        assert!(matches!(result, Err(InputError::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe));
+    }
+
+    #[test]
+    fn backends_may_prompt_while_they_run() {
+        let log = Scripted::default();
+        let (first, second) = with_input_backend(Nested(log.clone()), || (ask_number("Outer: "), ask_number("Again: ")));
+        assert_eq!((first.unwrap(), second.unwrap()), (10, 10));
+        assert_eq!(log.transcript(), "Outer: Inner: 5\n10\nAgain: Inner: 5\n10\n");
+    }
+}
@@ -0,0 +1,230 @@
+//! Runtime color mode selection.
+//!
+//! Styled text, tables, matrices and `input!` messages are always rendered
+//! with ANSI sequences; this module decides, right before the text is
+//! written, whether those sequences are kept or stripped. The decision is
+//! made per output stream so that `cargo run | less` loses colors on stdout
+//! while diagnostics on a terminal stderr keep them.
+//!
+//! # Modes
+//!
+//! - `ColorMode::Auto` (default) - Detect from the environment and terminal
+//! - `ColorMode::Always` - Always emit escape sequences
+//! - `ColorMode::Never` - Never emit escape sequences
+//!
+//! The mode can be changed globally with `set_color_mode`, e.g. from a
+//! `--color=<auto|always|never>` command line flag parsed with `FromStr`.
+//!
+//! # Auto Detection
+//!
+//! In `Auto` mode the following rules apply, first match wins:
+//! 1. `CLICOLOR_FORCE` set and not `0` → colors enabled
+//! 2. `NO_COLOR` set and not empty → colors disabled
+//! 3. `CLICOLOR=0` → colors disabled
+//! 4. `TERM=dumb` → colors disabled
+//! 5. Otherwise colors are enabled only if the stream is a terminal
+//!
+//! The environment is read once per stream and cached for the lifetime of
+//! the process.
+//!
+//! # Color Depth
+//!
+//! When colors are enabled, 256-color and truecolor sequences are downgraded
+//! to what the terminal advertises:
+//! - `COLORTERM=truecolor`/`24bit` (or Windows Terminal) → `ColorLevel::TrueColor`
+//! - `TERM` containing `256color` → `ColorLevel::Ansi256`
+//! - Anything else → `ColorLevel::Ansi16`
+//!
+//! Only SGR sequences (CSI sequences ending in `m`) are rewritten; other
+//! control sequences such as `\x1b[2K` pass through unchanged.
+//!
+//! # Destinations
+//!
+//! `println!`/`print!` follow stdout and `eprintln!`/`eprint!` follow stderr.
+//! `format!` and `write!`/`writeln!` cannot know where their result ends up
+//! and follow stdout.
+//!
+//! Only the macros apply the mode. The runtime formatters called directly
+//! (`format_table`, `format_table_with`, `format_matrix`, `format_determinant`,
+//! `format_container`) always return ANSI sequences; pass their result
+//! through `apply_color_mode(text, stream)` before writing it. Table exports
+//! (`export_table`) never contain colors.
+
+use crate::colorstyle::Color;
+use crate::common::{ansi_sequence_len, clean_ansi_for_width};
+use std::io::IsTerminal;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputStream {
+    #[default]
+    Stdout,
+    Stderr,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorLevel {
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+static COLOR_MODE: AtomicU8 = AtomicU8::new(0);
+static STDOUT_COLORS: OnceLock<bool> = OnceLock::new();
+static STDERR_COLORS: OnceLock<bool> = OnceLock::new();
+static COLOR_LEVEL: OnceLock<ColorLevel> = OnceLock::new();
+
+impl FromStr for ColorMode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            other => Err(format!("invalid color mode `{}` (expected auto, always or never)", other)),
+        }
+    }
+}
+pub fn set_color_mode(mode: ColorMode) {
+    let value = match mode {
+        ColorMode::Auto => 0,
+        ColorMode::Always => 1,
+        ColorMode::Never => 2,
+    };
+    COLOR_MODE.store(value, Ordering::Relaxed);
+}
+pub fn color_mode() -> ColorMode {
+    match COLOR_MODE.load(Ordering::Relaxed) {
+        1 => ColorMode::Always,
+        2 => ColorMode::Never,
+        _ => ColorMode::Auto,
+    }
+}
+fn env_is(name: &str, predicate: impl Fn(&str) -> bool) -> bool {
+    std::env::var_os(name).is_some_and(|v| predicate(&v.to_string_lossy()))
+}
+fn detect_colors(stream: OutputStream) -> bool {
+    if env_is("CLICOLOR_FORCE", |v| v != "0") {
+        return true;
+    }
+    if env_is("NO_COLOR", |v| !v.is_empty()) || env_is("CLICOLOR", |v| v == "0") || env_is("TERM", |v| v == "dumb") {
+        return false;
+    }
+    match stream {
+        OutputStream::Stdout => std::io::stdout().is_terminal(),
+        OutputStream::Stderr => std::io::stderr().is_terminal(),
+    }
+}
+pub fn colors_enabled(stream: OutputStream) -> bool {
+    match color_mode() {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => match stream {
+            OutputStream::Stdout => *STDOUT_COLORS.get_or_init(|| detect_colors(stream)),
+            OutputStream::Stderr => *STDERR_COLORS.get_or_init(|| detect_colors(stream)),
+        },
+    }
+}
+pub fn color_level() -> ColorLevel {
+    *COLOR_LEVEL.get_or_init(|| {
+        let term = std::env::var("TERM").unwrap_or_default();
+        if env_is("COLORTERM", |v| v == "truecolor" || v == "24bit")
+            || term.contains("truecolor") || term.contains("direct") || std::env::var_os("WT_SESSION").is_some() {
+            ColorLevel::TrueColor
+        } else if term.contains("256color") {
+            ColorLevel::Ansi256
+        } else {
+            ColorLevel::Ansi16
+        }
+    })
+}
+fn downgrade_params(params: &str, level: ColorLevel) -> String {
+    let values: Vec<&str> = params.split(';').collect();
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < values.len() {
+        let background = values[i] == "48";
+        let number = |offset: usize| values.get(i + offset).and_then(|v| v.parse::<u8>().ok());
+        let extended = match (values[i], values.get(i + 1).copied()) {
+            ("38" | "48", Some("5")) => number(2).map(|n| (Color::Ansi256(n), 3)),
+            ("38" | "48", Some("2")) => match (number(2), number(3), number(4)) {
+                (Some(r), Some(g), Some(b)) => Some((Color::Rgb(r, g, b), 5)),
+                _ => None,
+            },
+            _ => None,
+        };
+        match extended {
+            Some((color, consumed)) => {
+                let color = match level {
+                    ColorLevel::Ansi16 => Color::Ansi(color.to_ansi16()),
+                    _ => Color::Ansi256(color.to_ansi256()),
+                };
+                result.push(color.sgr_params(background));
+                i += consumed;
+            },
+            None => {
+                result.push(values[i].to_string());
+                i += 1;
+            }
+        }
+    }
+    result.join(";")
+}
+fn downgrade_colors(text: &str, level: ColorLevel) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+    while let Some(start) = text[i..].find('\x1B').map(|j| i + j) {
+        result.push_str(&text[i..start]);
+        let end = start + ansi_sequence_len(&text[start..]).unwrap_or(1);
+        let sequence = &text[start..end];
+        match sequence.strip_prefix("\x1B[").and_then(|params| params.strip_suffix('m')) {
+            Some(params) => {
+                result.push_str("\x1B[");
+                result.push_str(&downgrade_params(params, level));
+                result.push('m');
+            },
+            None => result.push_str(sequence),
+        }
+        i = end;
+    }
+    result.push_str(&text[i..]);
+    result
+}
+pub fn apply_color_mode(text: String, stream: OutputStream) -> String {
+    if !text.contains('\x1B') {
+        return text;
+    }
+    if !colors_enabled(stream) {
+        return clean_ansi_for_width(&text);
+    }
+    match color_level() {
+        ColorLevel::TrueColor => text,
+        level if text.contains("8;5;") || text.contains("8;2;") => downgrade_colors(&text, level),
+        _ => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extended_colors_are_downgraded() {
+        assert_eq!(downgrade_colors("\x1B[1;38;2;255;0;0mred\x1B[0m", ColorLevel::Ansi256), "\x1B[1;38;5;196mred\x1B[0m");
+        assert_eq!(downgrade_colors("\x1B[48;5;196mbg", ColorLevel::Ansi16), "\x1B[101mbg");
+    }
+
+    #[test]
+    fn other_sequences_pass_through() {
+        let text = "\x1B[2K\x1B[1G\x1B[38;5;196mx\x1B[0m";
+        assert_eq!(downgrade_colors(text, ColorLevel::Ansi16), "\x1B[2K\x1B[1G\x1B[91mx\x1B[0m");
+        assert_eq!(downgrade_colors("\x1B]0;title\x07\x1B[3Aup", ColorLevel::Ansi16), "\x1B]0;title\x07\x1B[3Aup");
+        assert_eq!(downgrade_colors("cut \x1B[38;5", ColorLevel::Ansi16), "cut \x1B[38;5");
+    }
+}
@@ -0,0 +1,512 @@
+//! ANSI color and style management module.
+//!
+//! This module provides comprehensive functions for working with ANSI escape sequences
+//! for text colors and styles in terminal environments. It serves as the core color
+//! engine for the println! macro system and handles all terminal styling operations,
+//! both for styles resolved at compile time and for dynamic `@({var})` styles.
+//!
+//! # ANSI Color System
+//!
+//! The module implements the full ANSI color specification with support for:
+//! - 8 standard colors (30-37)
+//! - 8 bright colors (90-97) 
+//! - 16 background colors (40-47, 100-107)
+//! - 9 text style modifiers (1-9)
+//! - Automatic reset sequences (0)
+//!
+//! # Supported Colors
+//!
+//! ## Standard Colors (30-37)
+//! - black, red, green, yellow, blue, magenta, cyan, white
+//!
+//! ## Bright Colors (90-97)  
+//! - bright_black (alias: gray), bright_red, bright_green, bright_yellow
+//! - bright_blue, bright_magenta, bright_cyan, bright_white
+//!
+//! ## Background Colors (40-47, 100-107)
+//! - Any color name prefixed with `on_`: on_red, on_bright_blue, on_gray, ...
+//! - Combines freely with a foreground: `@(white, on_red, bold)`
+//!
+//! ## Extended Colors (38;5 / 38;2)
+//! - 256-color palette: `color(208)`
+//! - 24-bit truecolor: `#ff8800`, `#f80`, `rgb(255, 136, 0)`
+//! - Backgrounds use the same `on_` prefix: `on_#003366`, `on_color(17)`
+//!
+//! Extended colors are always generated at full precision; the color mode
+//! (see `colormode`) downgrades them to the nearest 256-color or 16-color
+//! value when the terminal does not advertise truecolor support.
+//!
+//! # Supported Styles
+//!
+//! ## Text Modifications (1-9)
+//! - bold (1) - Increased font weight
+//! - dimmed (2) - Decreased font weight  
+//! - italic (3) - Slanted text style
+//! - underline (4) - Underlined text
+//! - blink (5) - Blinking text effect
+//! - reversed (7) - Inverted foreground/background
+//! - hidden (8) - Invisible text (password fields)
+//! - strikethrough (9) - Line through text
+//!
+//! # Style Values
+//!
+//! `Style` bundles a foreground `Color`, a background `Color` and a set of
+//! `Attribute`s. It is built either with builder methods or parsed from the
+//! same syntax as `@(...)`:
+//! - Builder: `Style::new().fg(Color::RED).bg(Color::Rgb(0, 51, 102)).bold()`
+//! - Parsing: `"red, on_#003366, bold".parse::<Style>()`
+//!
+//! Parsing is strict and reports the first unknown name. Any `ToStyle` value
+//! (`Style`, `&str`, `String`) can be used as a dynamic `@({var})` style;
+//! strings are parsed leniently there, skipping unknown names, since output
+//! cannot fail at that point. Parse once with `FromStr` to validate them.
+//!
+//! # ANSI Sequence Generation
+//!
+//! Color sequences follow the standard format: `\x1B[{codes}m`
+//! - Single style: `\x1B[31m` (red)
+//! - Multiple styles: `\x1B[31;1m` (red + bold)
+//! - Reset sequence: `\x1B[0m` (clear all styles)
+//!
+//! # Performance Optimizations
+//!
+//! - **Zero Allocation**: Returns reset sequence for empty inputs
+//! - **Efficient Joining**: Uses semicolon-separated code concatenation
+//! - **Fast Matching**: Uses optimized match expressions for style lookup
+//! - **String Interning**: Reuses common ANSI sequences
+//!
+//! # Terminal Compatibility
+//!
+//! The module generates standard ANSI sequences compatible with:
+//! - Unix terminals (xterm, gnome-terminal, etc.)
+//! - Windows Terminal and PowerShell 
+//! - VS Code integrated terminal
+//! - Modern terminal emulators
+//!
+//! # Usage Patterns
+//!
+//! The module is used internally by the formatting system:
+//! - Style parsing: `@(red, bold)` → `["red", "bold"]`
+//! - Code generation: `["red", "bold"]` → `\x1B[31;1m`
+//! - Reset handling: Automatic style reset after each token
+//!
+//! # Technical Implementation
+//!
+//! ## Error Handling
+//! - Unknown styles are silently ignored (graceful degradation)
+//! - Empty style lists return reset sequences
+//! - Invalid codes are filtered out automatically
+//!
+//! ## Memory Management
+//! - Minimal heap allocations through strategic string building
+//! - Code vector reuse for multiple style combinations
+//! - Efficient string concatenation patterns
+//!
+//! ## Standards Compliance
+//! - Full ANSI X3.64 compliance for color codes
+//! - SGR (Select Graphic Rendition) parameter support
+//! - Cross-platform terminal compatibility guaranteed
+
+use std::str::FromStr;
+
+const ANSI_NAMES: [&str; 16] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    "bright_black", "bright_red", "bright_green", "bright_yellow",
+    "bright_blue", "bright_magenta", "bright_cyan", "bright_white",
+];
+const ANSI_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0), (205, 0, 0), (0, 205, 0), (205, 205, 0),
+    (0, 0, 238), (205, 0, 205), (0, 205, 205), (229, 229, 229),
+    (127, 127, 127), (255, 0, 0), (0, 255, 0), (255, 255, 0),
+    (92, 92, 255), (255, 0, 255), (0, 255, 255), (255, 255, 255),
+];
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Ansi(u8),
+    Ansi256(u8),
+    Rgb(u8, u8, u8),
+}
+impl FromStr for Color {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || format!("invalid color `{}` (expected a color name, #rrggbb, rgb(r, g, b) or color(0-255))", s);
+        if s == "gray" {
+            return Ok(Color::Ansi(8));
+        }
+        if let Some(index) = ANSI_NAMES.iter().position(|name| *name == s) {
+            return Ok(Color::Ansi(index as u8));
+        }
+        if let Some(hex) = s.strip_prefix('#') {
+            let digits: Vec<u8> = hex.chars().map(|c| c.to_digit(16).map(|d| d as u8)).collect::<Option<_>>().ok_or_else(invalid)?;
+            return match digits[..] {
+                [r, g, b] => Ok(Color::Rgb(r * 17, g * 17, b * 17)),
+                [r1, r2, g1, g2, b1, b2] => Ok(Color::Rgb(r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2)),
+                _ => Err(invalid()),
+            };
+        }
+        let call_args = |name: &str| {
+            s.strip_prefix(name)
+                .and_then(|rest| rest.trim_start().strip_prefix('('))
+                .and_then(|rest| rest.strip_suffix(')'))
+                .map(|args| args.split(',').map(|a| a.trim().parse::<u8>()).collect::<Result<Vec<u8>, _>>())
+        };
+        if let Some(args) = call_args("rgb") {
+            return match args.map_err(|_| invalid())?[..] {
+                [r, g, b] => Ok(Color::Rgb(r, g, b)),
+                _ => Err(invalid()),
+            };
+        }
+        if let Some(args) = call_args("color") {
+            return match args.map_err(|_| invalid())?[..] {
+                [n] => Ok(Color::Ansi256(n)),
+                _ => Err(invalid()),
+            };
+        }
+        Err(invalid())
+    }
+}
+impl Color {
+    pub const BLACK: Color = Color::Ansi(0);
+    pub const RED: Color = Color::Ansi(1);
+    pub const GREEN: Color = Color::Ansi(2);
+    pub const YELLOW: Color = Color::Ansi(3);
+    pub const BLUE: Color = Color::Ansi(4);
+    pub const MAGENTA: Color = Color::Ansi(5);
+    pub const CYAN: Color = Color::Ansi(6);
+    pub const WHITE: Color = Color::Ansi(7);
+    pub const BRIGHT_BLACK: Color = Color::Ansi(8);
+    pub const BRIGHT_RED: Color = Color::Ansi(9);
+    pub const BRIGHT_GREEN: Color = Color::Ansi(10);
+    pub const BRIGHT_YELLOW: Color = Color::Ansi(11);
+    pub const BRIGHT_BLUE: Color = Color::Ansi(12);
+    pub const BRIGHT_MAGENTA: Color = Color::Ansi(13);
+    pub const BRIGHT_CYAN: Color = Color::Ansi(14);
+    pub const BRIGHT_WHITE: Color = Color::Ansi(15);
+    pub(crate) fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Ansi(index) => ANSI_PALETTE[index as usize % 16],
+            Color::Ansi256(n) if n < 16 => ANSI_PALETTE[n as usize],
+            Color::Ansi256(n) if n < 232 => {
+                let n = n - 16;
+                (CUBE_LEVELS[(n / 36) as usize], CUBE_LEVELS[(n / 6 % 6) as usize], CUBE_LEVELS[(n % 6) as usize])
+            },
+            Color::Ansi256(n) => {
+                let level = 8 + (n - 232) * 10;
+                (level, level, level)
+            },
+            Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+    pub(crate) fn to_ansi256(self) -> u8 {
+        let (r, g, b) = match self {
+            Color::Ansi(index) => return index,
+            Color::Ansi256(n) => return n,
+            Color::Rgb(r, g, b) => (r, g, b),
+        };
+        let cube_index = |v: u8| {
+            (0..CUBE_LEVELS.len()).min_by_key(|&i| (CUBE_LEVELS[i] as i32 - v as i32).abs()).unwrap_or(0) as u8
+        };
+        let cube = 16 + 36 * cube_index(r) + 6 * cube_index(g) + cube_index(b);
+        let average = (r as u32 + g as u32 + b as u32) / 3;
+        let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+        let target = Color::Rgb(r, g, b);
+        if distance(Color::Ansi256(gray).to_rgb(), target.to_rgb()) < distance(Color::Ansi256(cube).to_rgb(), target.to_rgb()) {
+            gray
+        } else {
+            cube
+        }
+    }
+    pub(crate) fn to_ansi16(self) -> u8 {
+        if let Color::Ansi(index) = self {
+            return index;
+        }
+        let rgb = self.to_rgb();
+        (0..16u8).min_by_key(|&i| distance(ANSI_PALETTE[i as usize], rgb)).unwrap_or(0)
+    }
+    pub(crate) fn sgr_params(self, background: bool) -> String {
+        let base = if background { 40 } else { 30 };
+        match self {
+            Color::Ansi(index) if index < 8 => (base + index as u32).to_string(),
+            Color::Ansi(index) => (base + 60 + (index as u32 - 8)).to_string(),
+            Color::Ansi256(n) => format!("{};5;{}", base + 8, n),
+            Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        }
+    }
+}
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+pub fn split_style_list(list: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    for c in list.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                terms.push(current.trim().to_string());
+                current.clear();
+                continue;
+            },
+            _ => {}
+        }
+        current.push(c);
+    }
+    terms.push(current.trim().to_string());
+    terms
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Attribute {
+    Bold,
+    Dimmed,
+    Italic,
+    Underline,
+    Blink,
+    Reversed,
+    Hidden,
+    Strikethrough,
+}
+impl Attribute {
+    fn sgr_code(self) -> u8 {
+        match self {
+            Attribute::Bold => 1,
+            Attribute::Dimmed => 2,
+            Attribute::Italic => 3,
+            Attribute::Underline => 4,
+            Attribute::Blink => 5,
+            Attribute::Reversed => 7,
+            Attribute::Hidden => 8,
+            Attribute::Strikethrough => 9,
+        }
+    }
+}
+impl FromStr for Attribute {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "bold" => Ok(Attribute::Bold),
+            "dimmed" => Ok(Attribute::Dimmed),
+            "italic" => Ok(Attribute::Italic),
+            "underline" => Ok(Attribute::Underline),
+            "blink" => Ok(Attribute::Blink),
+            "reversed" => Ok(Attribute::Reversed),
+            "hidden" => Ok(Attribute::Hidden),
+            "strikethrough" => Ok(Attribute::Strikethrough),
+            other => Err(format!("unknown attribute `{}`", other)),
+        }
+    }
+}
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    fg: Option<Color>,
+    bg: Option<Color>,
+    attributes: Vec<Attribute>,
+}
+impl Style {
+    pub fn new() -> Self {
+        Style::default()
+    }
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+    pub fn bg(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+    pub fn attribute(mut self, attribute: Attribute) -> Self {
+        if !self.attributes.contains(&attribute) {
+            self.attributes.push(attribute);
+        }
+        self
+    }
+    pub fn bold(self) -> Self { self.attribute(Attribute::Bold) }
+    pub fn dimmed(self) -> Self { self.attribute(Attribute::Dimmed) }
+    pub fn italic(self) -> Self { self.attribute(Attribute::Italic) }
+    pub fn underline(self) -> Self { self.attribute(Attribute::Underline) }
+    pub fn blink(self) -> Self { self.attribute(Attribute::Blink) }
+    pub fn reversed(self) -> Self { self.attribute(Attribute::Reversed) }
+    pub fn hidden(self) -> Self { self.attribute(Attribute::Hidden) }
+    pub fn strikethrough(self) -> Self { self.attribute(Attribute::Strikethrough) }
+    pub fn foreground(&self) -> Option<Color> {
+        self.fg
+    }
+    pub fn background(&self) -> Option<Color> {
+        self.bg
+    }
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.attributes
+    }
+    pub fn patch(&self, other: &Style) -> Style {
+        let mut patched = self.clone();
+        patched.fg = other.fg.or(self.fg);
+        patched.bg = other.bg.or(self.bg);
+        for attribute in &other.attributes {
+            patched = patched.attribute(*attribute);
+        }
+        patched
+    }
+    pub(crate) fn without(mut self, attribute: Attribute) -> Style {
+        self.attributes.retain(|a| *a != attribute);
+        self
+    }
+    pub fn is_plain(&self) -> bool {
+        self.fg.is_none() && self.bg.is_none() && self.attributes.is_empty()
+    }
+    fn with_term(self, term: &str) -> Result<Self, String> {
+        let term = term.trim();
+        if let Ok(color) = term.parse::<Color>() {
+            return Ok(self.fg(color));
+        }
+        if let Some(color) = term.strip_prefix("on_") {
+            return color.parse::<Color>().map(|color| self.bg(color));
+        }
+        term.parse::<Attribute>()
+            .map(|attribute| self.attribute(attribute))
+            .map_err(|_| format!("unknown color or style `{}`", term))
+    }
+    pub fn parse_lossy(list: &str) -> Self {
+        split_style_list(list).iter().fold(Style::new(), |style, term| {
+            style.clone().with_term(term).unwrap_or(style)
+        })
+    }
+    pub fn ansi_code(&self) -> String {
+        let mut codes: Vec<String> = Vec::new();
+        codes.extend(self.fg.map(|color| color.sgr_params(false)));
+        codes.extend(self.bg.map(|color| color.sgr_params(true)));
+        codes.extend(self.attributes.iter().map(|a| a.sgr_code().to_string()));
+        if codes.is_empty() {
+            return "\x1B[0m".to_string();
+        }
+        format!("\x1B[{}m", codes.join(";"))
+    }
+}
+impl FromStr for Style {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Ok(Style::new());
+        }
+        split_style_list(s).iter().try_fold(Style::new(), |style, term| style.with_term(term))
+    }
+}
+pub trait ToStyle {
+    fn to_style(&self) -> Style;
+}
+impl ToStyle for Style {
+    fn to_style(&self) -> Style {
+        self.clone()
+    }
+}
+impl ToStyle for str {
+    fn to_style(&self) -> Style {
+        Style::parse_lossy(self)
+    }
+}
+impl ToStyle for String {
+    fn to_style(&self) -> Style {
+        Style::parse_lossy(self)
+    }
+}
+impl<T: ToStyle + ?Sized> ToStyle for &T {
+    fn to_style(&self) -> Style {
+        (**self).to_style()
+    }
+}
+pub fn ansi_code_for_style(styles: &[String]) -> String {
+    styles.iter()
+        .fold(Style::new(), |style, term| style.clone().with_term(term).unwrap_or(style))
+        .ansi_code()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn on_prefix_sets_the_background() {
+        let code = |list: &str| ansi_code_for_style(&split_style_list(list));
+        assert_eq!(code("on_red"), "\x1B[41m");
+        assert_eq!(code("on_bright_blue"), "\x1B[104m");
+        assert_eq!(code("on_gray"), "\x1B[100m");
+        assert_eq!(code("on_#ff0000"), "\x1B[48;2;255;0;0m");
+        assert_eq!(code("on_color(17)"), "\x1B[48;5;17m");
+        assert_eq!(code("white, on_red, bold"), "\x1B[37;41;1m");
+        assert_eq!(code("on_blue, #ff8800"), "\x1B[38;2;255;136;0;44m");
+        assert_eq!("yellow, on_rgb(0, 51, 102)".parse::<Style>(), Ok(Style::new().fg(Color::YELLOW).bg(Color::Rgb(0, 51, 102))));
+    }
+
+    #[test]
+    fn extended_colors_are_parsed() {
+        assert_eq!("#f80".parse::<Color>(), Ok(Color::Rgb(255, 136, 0)));
+        assert_eq!("#FF8800".parse::<Color>(), Ok(Color::Rgb(255, 136, 0)));
+        assert_eq!("rgb(255, 136, 0)".parse::<Color>(), Ok(Color::Rgb(255, 136, 0)));
+        assert_eq!("rgb (1,2,3)".parse::<Color>(), Ok(Color::Rgb(1, 2, 3)));
+        assert_eq!("color(208)".parse::<Color>(), Ok(Color::Ansi256(208)));
+        assert_eq!("gray".parse::<Color>(), Ok(Color::BRIGHT_BLACK));
+        assert_eq!(Style::new().fg(Color::Ansi256(208)).bg(Color::Rgb(0, 51, 102)).ansi_code(), "\x1B[38;5;208;48;2;0;51;102m");
+    }
+
+    #[test]
+    fn malformed_colors_are_rejected() {
+        for input in ["rgb(256,0,0)", "rgb(1,2)", "rgb(1,2,3", "#12", "#12345", "#ggg", "color(300)", "color()", "purple"] {
+            assert_eq!(input.parse::<Color>(), Err(format!(
+                "invalid color `{}` (expected a color name, #rrggbb, rgb(r, g, b) or color(0-255))", input)));
+        }
+    }
+
+    #[test]
+    fn colors_downgrade_to_the_nearest_palette_entry() {
+        assert_eq!(Color::Rgb(255, 136, 0).to_ansi256(), 208);
+        assert_eq!(Color::Rgb(128, 128, 128).to_ansi256(), 244);
+        assert_eq!(Color::Rgb(0, 0, 0).to_ansi256(), 16);
+        assert_eq!(Color::Ansi256(17).to_ansi256(), 17);
+        assert_eq!(Color::YELLOW.to_ansi256(), 3);
+        assert_eq!(Color::Rgb(250, 5, 5).to_ansi16(), 9);
+        assert_eq!(Color::Rgb(0, 0, 230).to_ansi16(), 4);
+        assert_eq!(Color::Ansi256(196).to_ansi16(), 9);
+        assert_eq!(Color::Ansi256(244).to_ansi16(), 8);
+        assert_eq!(Color::BRIGHT_BLUE.to_ansi16(), 12);
+    }
+
+    #[test]
+    fn strict_parsing_reports_unknown_names() {
+        assert_eq!("red, sparkly".parse::<Style>(), Err("unknown color or style `sparkly`".to_string()));
+        assert_eq!("bold, blod".parse::<Style>(), Err("unknown color or style `blod`".to_string()));
+        assert!("on_purple".parse::<Style>().unwrap_err().starts_with("invalid color `purple`"));
+        assert_eq!("blod".parse::<Attribute>(), Err("unknown attribute `blod`".to_string()));
+    }
+
+    #[test]
+    fn empty_styles_are_plain_and_reset() {
+        let plain = " ".parse::<Style>().unwrap();
+        assert_eq!(plain, Style::new());
+        assert!(plain.is_plain());
+        assert_eq!(plain.ansi_code(), "\x1B[0m");
+        assert!(!Style::new().bold().is_plain());
+        assert_eq!(ansi_code_for_style(&[]), "\x1B[0m");
+    }
+
+    #[test]
+    fn patch_keeps_what_the_patch_leaves_unset() {
+        let base = Style::new().fg(Color::RED).bold();
+        let patched = base.patch(&Style::new().bg(Color::BLUE).italic());
+        assert_eq!(patched, Style::new().fg(Color::RED).bg(Color::BLUE).bold().italic());
+        assert_eq!(patched.ansi_code(), "\x1B[31;44;1;3m");
+        assert_eq!(base.patch(&Style::new().fg(Color::GREEN).bold()), Style::new().fg(Color::GREEN).bold());
+        assert_eq!(base.patch(&Style::new()), base);
+    }
+
+    #[test]
+    fn lossy_parsing_skips_unknown_names() {
+        assert_eq!(Style::parse_lossy("red, sparkly, bold"), Style::new().fg(Color::RED).bold());
+        assert_eq!(Style::parse_lossy("on_purple, #12"), Style::new());
+        assert_eq!("blue, blod".to_style(), Style::new().fg(Color::BLUE));
+        assert_eq!(ansi_code_for_style(&["green".to_string(), "nope".to_string()]), "\x1B[32m");
+    }
+}
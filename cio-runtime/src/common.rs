@@ -0,0 +1,325 @@
+//! Common utility functions used across multiple formatting modules.
+//!
+//! This collection provides essential parsing and analysis functions for
+//! structured data formatting. These utilities handle the complex task of
+//! analyzing nested data structures, bracket parsing, and string cleaning
+//! operations that are fundamental to advanced formatting capabilities.
+//!
+//! # Function Overview
+//!
+//! ## count_nesting_depth
+//! Analyzes the maximum nesting depth of bracket structures within a string.
+//! Essential for determining appropriate formatting strategies for nested
+//! containers like vectors of vectors, matrices, and complex data structures.
+//!
+//! Features:
+//! - Quote-aware parsing that ignores brackets within string literals
+//! - Accurate depth calculation for arbitrarily nested structures
+//! - Efficient single-pass algorithm with O(n) time complexity
+//! - Robust handling of malformed bracket sequences
+//!
+//! Use cases:
+//! - Matrix dimension detection for mathematical formatting
+//! - Container nesting analysis for pretty-printing decisions
+//! - Automatic indentation level calculation
+//! - Format strategy selection based on complexity
+//!
+//! ## find_first_level_brackets
+//! Locates all top-level bracket pairs within a string, providing precise
+//! position information for structured parsing operations.
+//!
+//! Features:
+//! - Returns precise start and end positions for each bracket pair
+//! - Quote-aware parsing prevents false matches in string literals
+//! - Handles multiple first-level sections in complex structures
+//! - Efficient vector-based collection of bracket positions
+//!
+//! Use cases:
+//! - Array element separation for table formatting
+//! - Matrix row identification and parsing
+//! - Container boundary detection for formatting decisions
+//! - Structured data decomposition for specialized renderers
+//!
+//! ## clean_string_quotes
+//! Performs intelligent string quote removal with safety checks and validation.
+//! Essential for processing string literals that may contain formatting data.
+//!
+//! Features:
+//! - Safe quote removal with length validation
+//! - Preserves strings that don't have matching quotes
+//! - Automatic whitespace trimming for clean output
+//! - Handles empty strings and edge cases gracefully
+//!
+//! Use cases:
+//! - JSON string value extraction for table formatting
+//! - String literal processing in format specifiers
+//! - Clean text extraction from quoted data sources
+//! - Data sanitization for display formatting
+//!
+//! ## clean_ansi_for_width
+//! Removes ANSI escape sequences from a string, leaving only the printable
+//! text. Used to measure the visual width of colored cells and to strip
+//! colors from output when the color mode disables them.
+//!
+//! `ansi_sequence_len` finds where a sequence ends. A CSI sequence ends at
+//! its final byte (`@` to `~`), not only at `m`. So `\x1b[2K` is skipped
+//! just like `\x1b[1m`.
+//!
+//! ## display_width / pad_to_width
+//! Measure and pad text by the number of terminal columns it occupies rather
+//! than by its number of `char`s. ANSI sequences are ignored, East Asian wide
+//! and fullwidth characters count as two columns, combining marks and
+//! zero-width joiners as none, and emoji ZWJ sequences as a single emoji.
+//! Every alignment in `:t`, `:m` and `:d` goes through these two functions.
+//!
+//! ## wrap_to_width / truncate_to_width
+//! Fit text into a number of columns, either by breaking it into several
+//! lines at spaces (splitting words longer than a line) or by cutting it and
+//! ending it with `…`. Used when a table is narrowed to the terminal width.
+//! Text is only cut between grapheme clusters, so emoji ZWJ sequences and
+//! combining marks stay whole, and never inside an ANSI sequence. A
+//! truncated cell that loses escape codes ends with a reset.
+//!
+//! ## terminal_width
+//! Width of the terminal attached to the given output stream, falling back
+//! to the `COLUMNS` environment variable. `None` when neither is available
+//! (piped output).
+//!
+//! # Technical Implementation
+//!
+//! ## Performance Characteristics
+//! - All functions use single-pass algorithms where possible
+//! - Minimal memory allocations through efficient data structures
+//! - Quote-aware parsing prevents common pitfalls in string processing
+//! - Robust error handling for malformed input data
+//!
+//! ## Quote Handling Strategy
+//! The quote-aware parsing system handles several important cases:
+//! - Nested quotes within string literals
+//! - Escaped quotes and special characters
+//! - Mixed quote styles in complex data structures
+//! - Edge cases with unclosed or malformed quote sequences
+//!
+//! ## Integration Points
+//! These utilities integrate with:
+//! - Basic formatters for container structure analysis
+//! - Mathematical formatters for matrix parsing
+//! - Table formatters for data extraction and organization
+//! - Error handling systems for robust parsing operations
+//!
+//! # Usage Patterns
+//!
+//! The functions work together to provide comprehensive parsing support:
+//! 1. count_nesting_depth determines formatting complexity
+//! 2. find_first_level_brackets provides structural boundaries
+//! 3. clean_string_quotes sanitizes extracted data
+//! 4. Results feed into specialized formatting algorithms
+//!
+//! This coordinated approach ensures consistent and reliable parsing
+//! across all formatting modules while maintaining high performance
+//! and robust error handling capabilities.
+
+use crate::colormode::OutputStream;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+pub(crate) fn count_nesting_depth(s: &str) -> usize {
+    let mut depth = 0;
+    let mut max_depth = 0;
+    let mut in_quotes = false;
+    for c in s.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '[' if !in_quotes => {
+                depth += 1;
+                max_depth = std::cmp::max(max_depth, depth);
+            },
+            ']' if !in_quotes => depth -= 1,
+            _ => {}
+        }
+    }
+    max_depth
+}
+pub(crate) fn find_first_level_brackets(content: &str) -> Vec<(usize, usize)> {
+    let mut brackets = Vec::new();
+    let mut level = 0;
+    let mut in_quotes = false;
+    let mut start_pos = 0;
+    for (i, c) in content.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '[' if !in_quotes => {
+                if level == 0 { start_pos = i; }
+                level += 1;
+            },
+            ']' if !in_quotes => {
+                level -= 1;
+                if level == 0 { brackets.push((start_pos, i)); }
+            },
+            _ => {}
+        }
+    }
+    brackets
+}
+pub(crate) fn clean_string_quotes(s: &str) -> String {
+    let trimmed = s.trim();
+    if trimmed.len() >= 2 && trimmed.starts_with('"') && trimmed.ends_with('"') {
+        trimmed[1..trimmed.len()-1].to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+pub(crate) fn ansi_sequence_len(s: &str) -> Option<usize> {
+    let rest = s.strip_prefix('\x1B')?;
+    let len = match rest.as_bytes().first() {
+        Some(b'[') => rest[1..].bytes().position(|b| (0x40..=0x7E).contains(&b)).map_or(rest.len(), |i| i + 2),
+        Some(b']') => match rest.find(['\x07', '\x1B']) {
+            Some(i) if rest[i..].starts_with('\x07') => i + 1,
+            Some(i) => (i + 2).min(rest.len()),
+            None => rest.len(),
+        },
+        Some(_) => rest.chars().next().map_or(0, char::len_utf8),
+        None => 0,
+    };
+    Some(1 + len)
+}
+pub(crate) fn clean_ansi_for_width(s: &str) -> String {
+    let mut result = String::new();
+    let mut i = 0;
+    while i < s.len() {
+        match ansi_sequence_len(&s[i..]) {
+            Some(len) => i += len,
+            None => {
+                let end = s[i..].find('\x1B').map_or(s.len(), |j| i + j);
+                result.push_str(&s[i..end]);
+                i = end;
+            },
+        }
+    }
+    result
+}
+pub(crate) fn display_width(s: &str) -> usize {
+    clean_ansi_for_width(s).width()
+}
+pub(crate) fn pad_to_width(s: &str, width: usize, align: char) -> String {
+    let missing = width.saturating_sub(display_width(s));
+    let left = match align {
+        'r' => missing,
+        'c' => missing / 2,
+        _ => 0,
+    };
+    format!("{}{}{}", " ".repeat(left), s, " ".repeat(missing - left))
+}
+fn split_at_width(text: &str, width: usize) -> (&str, &str) {
+    let mut used = 0;
+    let mut taken = false;
+    let mut i = 0;
+    while i < text.len() {
+        if let Some(len) = ansi_sequence_len(&text[i..]) {
+            i += len;
+            continue;
+        }
+        let end = text[i..].find('\x1B').map_or(text.len(), |j| i + j);
+        for (j, cluster) in text[i..end].grapheme_indices(true) {
+            let w = cluster.width();
+            if used + w > width && taken {
+                return (&text[..i + j], &text[i + j..]);
+            }
+            used += w;
+            taken = true;
+        }
+        i = end;
+    }
+    (text, "")
+}
+pub(crate) fn wrap_to_width(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && display_width(&line) + 1 + display_width(word) <= width {
+            line.push(' ');
+            line.push_str(word);
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        let mut rest = word;
+        while display_width(rest) > width {
+            let (head, tail) = split_at_width(rest, width);
+            lines.push(head.to_string());
+            rest = tail;
+        }
+        line.push_str(rest);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+pub(crate) fn truncate_to_width(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    let (head, tail) = split_at_width(text, width.saturating_sub(1));
+    let reset = if tail.contains('\x1B') { "\x1B[0m" } else { "" };
+    format!("{}…{}", head.trim_end(), reset)
+}
+pub(crate) fn terminal_width(stream: OutputStream) -> Option<usize> {
+    #[cfg(unix)]
+    {
+        let fd = match stream {
+            OutputStream::Stdout => libc::STDOUT_FILENO,
+            OutputStream::Stderr => libc::STDERR_FILENO,
+        };
+        let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+        if unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) } == 0 && size.ws_col > 0 {
+            return Some(size.ws_col as usize);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = stream;
+    std::env::var("COLUMNS").ok()?.trim().parse().ok().filter(|&columns| columns > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAMILY: &str = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+
+    #[test]
+    fn ansi_sequences_end_at_their_final_byte() {
+        assert_eq!(ansi_sequence_len("\x1B[1;31mred"), Some(7));
+        assert_eq!(ansi_sequence_len("\x1B[2Kline"), Some(4));
+        assert_eq!(ansi_sequence_len("\x1B]0;title\x07text"), Some(10));
+        assert_eq!(ansi_sequence_len("text"), None);
+        assert_eq!(clean_ansi_for_width("\x1B[2K\x1B[32mok\x1B[0m"), "ok");
+    }
+
+    #[test]
+    fn width_ignores_escapes_and_counts_clusters() {
+        assert_eq!(display_width("\x1B[31mabc\x1B[0m"), 3);
+        assert_eq!(display_width(FAMILY), 2);
+        assert_eq!(display_width("e\u{301}t\u{E9}"), 3);
+        assert_eq!(display_width("日本"), 4);
+    }
+
+    #[test]
+    fn splitting_keeps_clusters_and_escapes_whole() {
+        let text = format!("ab{}cd", FAMILY);
+        assert_eq!(split_at_width(&text, 3), ("ab", &text[2..]));
+        assert_eq!(split_at_width(&text, 4), (&text[..text.len() - 2], "cd"));
+        assert_eq!(split_at_width("\x1B[1;31mabcdef\x1B[0m", 3), ("\x1B[1;31mabc", "def\x1B[0m"));
+        assert_eq!(split_at_width("日本", 1), ("日", "本"));
+    }
+
+    #[test]
+    fn wrapping_and_truncation_measure_visible_text() {
+        assert_eq!(wrap_to_width("\x1B[32mgreen\x1B[0m leaf", 10), ["\x1B[32mgreen\x1B[0m leaf"]);
+        assert_eq!(wrap_to_width(&format!("{}{}{}", FAMILY, FAMILY, FAMILY), 4), [format!("{}{}", FAMILY, FAMILY), FAMILY.to_string()]);
+        assert_eq!(truncate_to_width("\x1B[31mwarning\x1B[0m", 5), "\x1B[31mwarn…\x1B[0m");
+        assert_eq!(truncate_to_width(&format!("{} family", FAMILY), 3), format!("{}…", FAMILY));
+        assert_eq!(truncate_to_width("short", 5), "short");
+    }
+}
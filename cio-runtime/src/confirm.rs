@@ -0,0 +1,101 @@
+//! Runtime support for the `confirm!` macro.
+//!
+//! Yes/no answers are matched case-insensitively against `y`/`yes` and
+//! `n`/`no`, plus any extra words registered for the whole program with
+//! `add_confirm_words` or passed to a single call with `yes = [...]` /
+//! `no = [...]`. An empty line yields the call's default, if it has one.
+//!
+//! # Hints
+//!
+//! The hint appended to the prompt reflects the default:
+//! - `default = true` → `[Y/n]`
+//! - `default = false` → `[y/N]`
+//! - no default → `[y/n]`, and an empty line is asked again
+//!
+//! # Localization
+//!
+//! ```text
+//! cio::runtime::add_confirm_words(&["oui", "o"], &["non"]);
+//! ```
+//! The hint keeps the `y`/`n` letters; put the local words in the prompt
+//! text itself when they should be shown.
+
+use crate::input::InputError;
+use std::sync::RwLock;
+
+type ConfirmWords = (Vec<String>, Vec<String>);
+static EXTRA_WORDS: RwLock<ConfirmWords> = RwLock::new((Vec::new(), Vec::new()));
+
+pub fn add_confirm_words(yes: &[&str], no: &[&str]) {
+    let mut words = EXTRA_WORDS.write().unwrap_or_else(|e| e.into_inner());
+    words.0.extend(yes.iter().map(|w| w.to_lowercase()));
+    words.1.extend(no.iter().map(|w| w.to_lowercase()));
+}
+pub fn confirm_hint(default: Option<bool>) -> &'static str {
+    match default {
+        Some(true) => "Y/n",
+        Some(false) => "y/N",
+        None => "y/n",
+    }
+}
+pub fn parse_confirm(line: &str, default: Option<bool>, yes: &[&str], no: &[&str]) -> Result<bool, InputError> {
+    match_confirm(line, default, yes, no, &EXTRA_WORDS.read().unwrap_or_else(|e| e.into_inner()))
+}
+fn match_confirm(line: &str, default: Option<bool>, yes: &[&str], no: &[&str], words: &ConfirmWords) -> Result<bool, InputError> {
+    let answer = line.trim().to_lowercase();
+    if answer.is_empty() {
+        return default.ok_or(InputError::Empty);
+    }
+    let matches = |builtin: [&str; 2], call: &[&str], global: &[String]| {
+        builtin.contains(&answer.as_str())
+            || call.iter().any(|w| w.to_lowercase() == answer)
+            || global.contains(&answer)
+    };
+    if matches(["y", "yes"], yes, &words.0) {
+        Ok(true)
+    } else if matches(["n", "no"], no, &words.1) {
+        Ok(false)
+    } else {
+        Err(InputError::Invalid("Please answer yes or no".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::error_message;
+
+    #[test]
+    fn builtin_words_match_case_insensitively() {
+        assert!(parse_confirm("y", None, &[], &[]).unwrap());
+        assert!(parse_confirm(" YES ", None, &[], &[]).unwrap());
+        assert!(!parse_confirm("N", None, &[], &[]).unwrap());
+        assert!(!parse_confirm("no", Some(true), &[], &[]).unwrap());
+    }
+
+    #[test]
+    fn empty_answer_uses_the_default() {
+        assert!(parse_confirm("", Some(true), &[], &[]).unwrap());
+        assert!(!parse_confirm("   ", Some(false), &[], &[]).unwrap());
+        assert!(matches!(parse_confirm("", None, &[], &[]), Err(InputError::Empty)));
+        assert_eq!((confirm_hint(Some(true)), confirm_hint(Some(false)), confirm_hint(None)), ("Y/n", "y/N", "y/n"));
+    }
+
+    #[test]
+    fn extra_words_from_the_call_and_the_program() {
+        assert!(parse_confirm("Ja", None, &["ja"], &["nein"]).unwrap());
+        assert!(!parse_confirm("NEIN", None, &["ja"], &["nein"]).unwrap());
+        let program = (vec!["oui".to_string(), "o".to_string()], vec!["non".to_string()]);
+        assert!(match_confirm("OUI", None, &[], &[], &program).unwrap());
+        assert!(!match_confirm("Non", Some(true), &["ja"], &[], &program).unwrap());
+        assert!(match_confirm("ja", None, &["ja"], &[], &program).unwrap());
+        assert_eq!(error_message(match_confirm("nein", None, &[], &[], &program)), "Please answer yes or no");
+    }
+
+    #[test]
+    fn other_answers_are_invalid() {
+        assert_eq!(error_message(parse_confirm("maybe", Some(true), &[], &[])), "Please answer yes or no");
+        assert_eq!(error_message(parse_confirm("yes please", None, &["ja"], &[])), "Please answer yes or no");
+        assert_eq!(error_message(parse_confirm("true", None, &[], &[])), "Please answer yes or no");
+    }
+}
@@ -0,0 +1,237 @@
+//! Line editing for `input!` and `try_input!` prompts.
+//!
+//! With the `line-editor` cargo feature, answers typed on a terminal are read
+//! through a line editor instead of the plain buffered `read_line`. Without
+//! the feature, or when the input backend is not the terminal (tests,
+//! `CIO_ANSWERS`, piped stdin), the prompt is written and the line is read as
+//! usual, and the options below are ignored.
+//!
+//! # Editing Keys
+//!
+//! - Left/Right, Home/End, Ctrl-A/Ctrl-E - move inside the line
+//! - Up/Down - recall earlier answers to the same prompt
+//! - Tab - complete the word before the cursor (with a completion callback)
+//! - Ctrl-D on an empty line - end of input
+//! - Ctrl-C - interrupts the program as usual
+//!
+//! # History
+//!
+//! Each prompt has its own history, keyed by the prompt text. With
+//! `LineOptions::history_file`, the history is keyed by the file instead, is
+//! loaded from it on first use and every accepted answer is appended to it.
+//! Blank answers and repeats of the previous answer are not recorded, and a
+//! history file that cannot be written does not interrupt the prompt.
+//!
+//! Histories are kept in one map for the whole process, keyed by history
+//! file or prompt text. Prompts sharing a file share one history, on every
+//! thread, and the file is only read the first time it is used: answers
+//! appended to it by other processes afterwards are not recalled.
+//!
+//! # Completion
+//!
+//! The completion callback receives the word before the cursor (the text
+//! since the last whitespace) and returns the candidates that may replace it.
+
+use crate::backend::write_output;
+use crate::input::{read_input_line, InputError};
+use std::path::{Path, PathBuf};
+
+type Completion<'a> = dyn Fn(&str) -> Vec<String> + 'a;
+#[derive(Default)]
+pub struct LineOptions<'a> {
+    history_file: Option<PathBuf>,
+    completion: Option<Box<Completion<'a>>>,
+}
+impl<'a> LineOptions<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn history_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.history_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+    pub fn completion(mut self, complete: impl Fn(&str) -> Vec<String> + 'a) -> Self {
+        self.completion = Some(Box::new(complete));
+        self
+    }
+}
+pub fn read_prompted_line(prompt: &str, options: LineOptions) -> Result<String, InputError> {
+    #[cfg(feature = "line-editor")]
+    {
+        use std::io::IsTerminal;
+        if crate::backend::terminal_backend_active() && std::io::stdin().is_terminal() && std::io::stdout().is_terminal() {
+            return line_editor::read_line(prompt, &options).map(|line| line.trim().to_string());
+        }
+    }
+    let _ = options;
+    write_output(prompt)?;
+    read_input_line()
+}
+#[cfg(any(feature = "line-editor", test))]
+fn complete_word(line: &str, pos: usize, complete: &Completion) -> (usize, Vec<String>) {
+    let start = line[..pos].char_indices().rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    (start, complete(&line[start..pos]))
+}
+#[cfg(any(feature = "line-editor", test))]
+mod history {
+    use std::collections::HashMap;
+    use std::io::Write;
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    static HISTORIES: Mutex<Option<HashMap<String, Vec<String>>>> = Mutex::new(None);
+
+    pub fn load_history(key: &str, file: Option<&Path>) -> Vec<String> {
+        let mut histories = HISTORIES.lock().unwrap_or_else(|e| e.into_inner());
+        histories.get_or_insert_with(HashMap::new).entry(key.to_string())
+            .or_insert_with(|| file
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .map(|text| text.lines().map(str::to_string).collect())
+                .unwrap_or_default())
+            .clone()
+    }
+    pub fn record_history(key: &str, file: Option<&Path>, line: &str) {
+        let mut histories = HISTORIES.lock().unwrap_or_else(|e| e.into_inner());
+        let history = histories.get_or_insert_with(HashMap::new).entry(key.to_string()).or_default();
+        if line.trim().is_empty() || history.last().is_some_and(|last| last == line) {
+            return;
+        }
+        history.push(line.to_string());
+        if let Some(path) = file
+            && let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(path) {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+    #[cfg(test)]
+    pub fn forget_history(key: &str) {
+        if let Some(histories) = HISTORIES.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            histories.remove(key);
+        }
+    }
+}
+
+#[cfg(feature = "line-editor")]
+mod line_editor {
+    use super::*;
+    use super::history::{load_history, record_history};
+    use rustyline::completion::{Completer, Pair};
+    use rustyline::error::ReadlineError;
+    use rustyline::highlight::Highlighter;
+    use rustyline::hint::Hinter;
+    use rustyline::history::DefaultHistory;
+    use rustyline::validate::Validator;
+    use rustyline::{CompletionType, Config, Context, Editor, Helper};
+    use std::io;
+
+    struct CioHelper<'a> {
+        completion: Option<&'a Completion<'a>>,
+    }
+    impl Completer for CioHelper<'_> {
+        type Candidate = Pair;
+        fn complete(&self, line: &str, pos: usize, _: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+            let Some(complete) = self.completion else {
+                return Ok((pos, Vec::new()));
+            };
+            let (start, candidates) = complete_word(line, pos, complete);
+            let candidates = candidates.into_iter()
+                .map(|candidate| Pair { display: candidate.clone(), replacement: candidate })
+                .collect();
+            Ok((start, candidates))
+        }
+    }
+    impl Hinter for CioHelper<'_> {
+        type Hint = String;
+    }
+    impl Highlighter for CioHelper<'_> {}
+    impl Validator for CioHelper<'_> {}
+    impl Helper for CioHelper<'_> {}
+
+    #[cfg(unix)]
+    fn interrupt() {
+        unsafe { libc::raise(libc::SIGINT) };
+    }
+    #[cfg(not(unix))]
+    fn interrupt() {
+        std::process::exit(130);
+    }
+    fn to_input_error(error: ReadlineError) -> InputError {
+        match error {
+            ReadlineError::Eof => InputError::Eof,
+            ReadlineError::Io(e) => InputError::Io(e),
+            ReadlineError::Interrupted => {
+                interrupt();
+                InputError::Io(io::Error::new(io::ErrorKind::Interrupted, "interrupted"))
+            },
+            other => InputError::Io(io::Error::other(other.to_string())),
+        }
+    }
+    pub fn read_line(prompt: &str, options: &LineOptions) -> Result<String, InputError> {
+        let file = options.history_file.as_deref();
+        let key = match file {
+            Some(path) => format!("file:{}", path.display()),
+            None => format!("prompt:{}", prompt),
+        };
+        let config = Config::builder().completion_type(CompletionType::List).auto_add_history(false).build();
+        let mut editor: Editor<CioHelper, DefaultHistory> = Editor::with_config(config).map_err(to_input_error)?;
+        editor.set_helper(Some(CioHelper { completion: options.completion.as_deref() }));
+        for entry in load_history(&key, file) {
+            let _ = editor.add_history_entry(entry);
+        }
+        let line = editor.readline(prompt).map_err(to_input_error)?;
+        record_history(&key, file, &line);
+        Ok(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::history::{forget_history, load_history, record_history};
+
+    fn fruits(word: &str) -> Vec<String> {
+        ["apple", "apricot", "banana"].iter().filter(|f| f.starts_with(word)).map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn completion_gets_the_word_before_the_cursor() {
+        assert_eq!(complete_word("eat ap", 6, &fruits), (4, vec!["apple".to_string(), "apricot".to_string()]));
+        assert_eq!(complete_word("b", 1, &fruits), (0, vec!["banana".to_string()]));
+        assert_eq!(complete_word("ap ban x", 6, &fruits), (3, vec!["banana".to_string()]));
+        assert_eq!(complete_word("é\u{3000}apr", 8, &fruits), (5, vec!["apricot".to_string()]));
+        assert_eq!(complete_word("eat ", 4, &fruits).1.len(), 3);
+        assert_eq!(complete_word("kiwi", 4, &fruits), (0, Vec::new()));
+    }
+
+    #[test]
+    fn history_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("cio-history-{}.txt", std::process::id()));
+        std::fs::write(&path, "first\n").unwrap();
+        let key = format!("file:{}", path.display());
+        assert_eq!(load_history(&key, Some(&path)), ["first"]);
+        for line in ["second", "second", "  ", "third"] {
+            record_history(&key, Some(&path), line);
+        }
+        assert_eq!(load_history(&key, Some(&path)), ["first", "second", "third"]);
+        std::fs::write(&path, "changed\n").unwrap();
+        assert_eq!(load_history(&key, Some(&path)), ["first", "second", "third"]);
+        std::fs::write(&path, "first\nsecond\nthird\n").unwrap();
+        forget_history(&key);
+        assert_eq!(load_history(&key, Some(&path)), ["first", "second", "third"]);
+        record_history(&key, Some(&path), "fourth");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\nsecond\nthird\nfourth\n");
+        forget_history(&key);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn prompt_histories_stay_in_memory() {
+        let key = "prompt:cio-test> ";
+        record_history(key, None, "one");
+        record_history(key, None, "two");
+        assert_eq!(load_history(key, None), ["one", "two"]);
+        forget_history(key);
+        assert!(load_history(key, None).is_empty());
+    }
+}
@@ -0,0 +1,267 @@
+//! Table export to Markdown, CSV, TSV and HTML.
+//!
+//! The exporters reuse the structure detection of the `:t` table formatter:
+//! the same data yields the same rows, headers and row labels, written as
+//! plain text for other tools instead of box drawing for the terminal.
+//!
+//! # Formats
+//!
+//! - `:md` - GitHub-flavoured Markdown table, for PR comments and docs
+//! - `:csv` - RFC 4180 comma-separated values, for spreadsheets
+//! - `:tsv` - tab-separated values, one line per record
+//! - `:html` - `<table>` with `<thead>`, `<tbody>` and `colspan`
+//!
+//! Custom headers work as with `:t`: `{data:csv(Name, Age)}`, and so does
+//! `labels=first|none` (only HTML marks row labels). The runtime entry
+//! points are `export_table(&value, &headers, TableFormat::Csv)` and
+//! `export_table_with`, which also takes `TableOptions`.
+//!
+//! # Format Details
+//!
+//! ## Markdown
+//! Markdown tables have exactly one header row:
+//! - Tables without headers get an empty header row
+//! - Multi-level headers (3D data) are joined per column: `Alice / T1`
+//! - Columns holding only numbers are right-aligned (`---:`)
+//! - `|` is escaped and line breaks become `<br>`
+//!
+//! ## CSV and TSV
+//! Every header row is written as a record, spanned header cells are left
+//! empty. Records end with CRLF, except the last.
+//! - CSV fields containing a comma, a quote or a line break are quoted and
+//!   quotes are doubled
+//! - TSV fields are never quoted: tabs and line breaks are written as `\t`,
+//!   `\n` and `\r`, and backslashes are doubled
+//!
+//! ## HTML
+//! Header rows go in `<thead>`, spanned headers use `colspan`, and row
+//! labels are `<th scope="row">`. Text is HTML-escaped.
+//!
+//! Cells never contain ANSI codes and the output has no trailing newline.
+
+use crate::common::{clean_ansi_for_width, display_width, pad_to_width};
+use crate::table::{build_table, calculate_colspans, table_cell, TableOptions};
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableFormat {
+    Markdown,
+    Csv,
+    Tsv,
+    Html,
+}
+impl FromStr for TableFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "md" | "markdown" => Ok(TableFormat::Markdown),
+            "csv" => Ok(TableFormat::Csv),
+            "tsv" => Ok(TableFormat::Tsv),
+            "html" => Ok(TableFormat::Html),
+            other => Err(format!("unknown table format `{}` (expected `md`, `csv`, `tsv` or `html`)", other)),
+        }
+    }
+}
+pub fn export_table<T>(value: &T, headers: &[String], format: TableFormat) -> String
+where
+    T: std::fmt::Debug + serde::Serialize,
+{
+    export_table_with(value, headers, format, &TableOptions::default())
+}
+pub fn export_table_with<T>(value: &T, headers: &[String], format: TableFormat, options: &TableOptions) -> String
+where
+    T: std::fmt::Debug + serde::Serialize,
+{
+    let (rows, n_headers, row_labels) = match serde_json::to_value(value) {
+        Ok(v) => match build_table(&v, headers) {
+            Some(data) => {
+                let row_labels = data.has_row_labels(options.label_mode());
+                (data.rows, data.n_headers, row_labels)
+            },
+            None => (vec![vec![table_cell(&v)]], 0, false),
+        },
+        Err(_) => (vec![vec![format!("{:?}", value)]], 0, false),
+    };
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let rows: Vec<Vec<String>> = rows.iter().map(|row| {
+        (0..columns).map(|i| row.get(i).map(|cell| clean_ansi_for_width(cell)).unwrap_or_default()).collect()
+    }).collect();
+    if rows.is_empty() {
+        return String::new();
+    }
+    match format {
+        TableFormat::Markdown => to_markdown(&rows, n_headers),
+        TableFormat::Csv => to_delimited(&rows, ",", csv_field),
+        TableFormat::Tsv => to_delimited(&rows, "\t", tsv_field),
+        TableFormat::Html => to_html(&rows, n_headers, row_labels),
+    }
+}
+fn joined_headers(rows: &[Vec<String>], n_headers: usize) -> Vec<String> {
+    let filled: Vec<Vec<String>> = rows[..n_headers].iter().map(|row| {
+        let mut last = String::new();
+        row.iter().enumerate().map(|(i, cell)| {
+            if !cell.is_empty() || i == 0 {
+                last = cell.clone();
+            }
+            last.clone()
+        }).collect()
+    }).collect();
+    (0..rows[0].len()).map(|i| {
+        let mut levels: Vec<&str> = filled.iter().map(|row| row[i].as_str()).filter(|cell| !cell.is_empty()).collect();
+        levels.dedup();
+        levels.join(" / ")
+    }).collect()
+}
+fn markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>")
+}
+fn to_markdown(rows: &[Vec<String>], n_headers: usize) -> String {
+    let header = if n_headers == 0 { vec![String::new(); rows[0].len()] } else { joined_headers(rows, n_headers) };
+    let body = &rows[n_headers.min(rows.len())..];
+    let numeric: Vec<bool> = (0..header.len()).map(|i| {
+        !body.is_empty() && body.iter().all(|row| row[i].parse::<f64>().is_ok())
+    }).collect();
+    let lines: Vec<Vec<String>> = std::iter::once(&header).chain(body)
+        .map(|row| row.iter().map(|cell| markdown_cell(cell)).collect())
+        .collect();
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| lines.iter().map(|row| display_width(&row[i])).max().unwrap_or(0).max(3))
+        .collect();
+    let format_line = |row: &[String]| {
+        let cells: Vec<String> = row.iter().enumerate()
+            .map(|(i, cell)| pad_to_width(cell, widths[i], if numeric[i] { 'r' } else { 'l' }))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let rule: Vec<String> = widths.iter().zip(&numeric)
+        .map(|(w, numeric)| if *numeric { format!("{}:", "-".repeat(w + 1)) } else { "-".repeat(w + 2) })
+        .collect();
+    let mut output = vec![format_line(&lines[0]), format!("|{}|", rule.join("|"))];
+    output.extend(lines[1..].iter().map(|row| format_line(row)));
+    output.join("\n")
+}
+fn csv_field(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+fn tsv_field(cell: &str) -> String {
+    let mut field = String::with_capacity(cell.len());
+    for c in cell.chars() {
+        match c {
+            '\\' => field.push_str("\\\\"),
+            '\t' => field.push_str("\\t"),
+            '\n' => field.push_str("\\n"),
+            '\r' => field.push_str("\\r"),
+            c => field.push(c),
+        }
+    }
+    field
+}
+fn to_delimited(rows: &[Vec<String>], delimiter: &str, field: fn(&str) -> String) -> String {
+    rows.iter().map(|row| {
+        row.iter().map(|cell| field(cell)).collect::<Vec<_>>().join(delimiter)
+    }).collect::<Vec<_>>().join("\r\n")
+}
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&#39;")
+}
+fn to_html(rows: &[Vec<String>], n_headers: usize, row_labels: bool) -> String {
+    let mut output = vec!["<table>".to_string()];
+    if n_headers > 0 {
+        output.push("  <thead>".to_string());
+        for row in &rows[..n_headers] {
+            let cells: String = row.iter().zip(calculate_colspans(row)).filter(|(_, span)| *span > 0).map(|(cell, span)| {
+                let colspan = if span > 1 { format!(" colspan=\"{}\"", span) } else { String::new() };
+                format!("<th{}>{}</th>", colspan, html_escape(cell))
+            }).collect();
+            output.push(format!("    <tr>{}</tr>", cells));
+        }
+        output.push("  </thead>".to_string());
+    }
+    if rows.len() > n_headers {
+        output.push("  <tbody>".to_string());
+        for row in &rows[n_headers..] {
+            let cells: String = row.iter().enumerate().map(|(i, cell)| {
+                if i == 0 && row_labels {
+                    format!("<th scope=\"row\">{}</th>", html_escape(cell))
+                } else {
+                    format!("<td>{}</td>", html_escape(cell))
+                }
+            }).collect();
+            output.push(format!("    <tr>{}</tr>", cells));
+        }
+        output.push("  </tbody>".to_string());
+    }
+    output.push("</table>".to_string());
+    output.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sales() -> serde_json::Value {
+        json!({
+            "Alice": {"T1": {"x": 1, "y": 2}, "T2": {"x": 3, "y": 4}},
+            "Bob": {"T1": {"x": 5, "y": 6}, "T2": {"x": 7, "y": 8}},
+        })
+    }
+    fn notes() -> serde_json::Value {
+        json!([{"name": "Smith, \"Jo\"", "note": "a\tb\nc"}, {"name": "<b>R&D</b>", "note": "C:\\tmp"}])
+    }
+
+    #[test]
+    fn markdown_joins_levels_and_aligns_numbers() {
+        assert_eq!(export_table(&sales(), &[], TableFormat::Markdown), "\
+|     | Alice / T1 | Alice / T2 | Bob / T1 | Bob / T2 |
+|-----|-----------:|-----------:|---------:|---------:|
+| x   |          1 |          3 |        5 |        7 |
+| y   |          2 |          4 |        6 |        8 |");
+        let table = export_table(&json!([{"cmd": "a|b", "out": "1\n2"}]), &[], TableFormat::Markdown);
+        assert_eq!(table.lines().last(), Some("| a\\|b | 1<br>2 |"));
+    }
+
+    #[test]
+    fn csv_quotes_special_fields() {
+        assert_eq!(export_table(&notes(), &[], TableFormat::Csv),
+                   "name,note\r\n\"Smith, \"\"Jo\"\"\",\"a\tb\nc\"\r\n<b>R&D</b>,C:\\tmp");
+        assert_eq!(export_table(&sales(), &[], TableFormat::Csv), ",Alice,,Bob,\r\n,T1,T2,T1,T2\r\nx,1,3,5,7\r\ny,2,4,6,8");
+    }
+
+    #[test]
+    fn tsv_escapes_instead_of_quoting() {
+        assert_eq!(export_table(&notes(), &[], TableFormat::Tsv),
+                   "name\tnote\r\nSmith, \"Jo\"\ta\\tb\\nc\r\n<b>R&D</b>\tC:\\\\tmp");
+        assert_eq!(export_table(&json!([{"k": "x", "v": "y\r"}]), &[], TableFormat::Tsv), "k\tv\r\nx\ty\\r");
+    }
+
+    #[test]
+    fn html_escapes_text_and_spans_headers() {
+        assert_eq!(export_table(&sales(), &[], TableFormat::Html), "\
+<table>
+  <thead>
+    <tr><th></th><th colspan=\"2\">Alice</th><th colspan=\"2\">Bob</th></tr>
+    <tr><th></th><th>T1</th><th>T2</th><th>T1</th><th>T2</th></tr>
+  </thead>
+  <tbody>
+    <tr><th scope=\"row\">x</th><td>1</td><td>3</td><td>5</td><td>7</td></tr>
+    <tr><th scope=\"row\">y</th><td>2</td><td>4</td><td>6</td><td>8</td></tr>
+  </tbody>
+</table>");
+        let table = export_table(&notes(), &[], TableFormat::Html);
+        assert!(table.contains("<tr><td>Smith, &quot;Jo&quot;</td><td>a\tb\nc</td></tr>"));
+        assert!(table.contains("<td>&lt;b&gt;R&amp;D&lt;/b&gt;</td>"));
+    }
+
+    #[test]
+    fn formats_parse_from_their_spec_names() {
+        assert_eq!("markdown".parse(), Ok(TableFormat::Markdown));
+        assert_eq!(" tsv ".parse(), Ok(TableFormat::Tsv));
+        assert_eq!("xls".parse::<TableFormat>().unwrap_err(),
+                   "unknown table format `xls` (expected `md`, `csv`, `tsv` or `html`)");
+    }
+}
@@ -0,0 +1,378 @@
+//! Runtime support for the `input!` and `try_input!` macros.
+//!
+//! The macros render their prompt with the cio style syntax and then call
+//! into this module to read and convert a line of input. Keeping the reading
+//! logic here means end-of-file, I/O failures and parse failures are told
+//! apart in one place instead of in every expansion.
+//!
+//! # Error Kinds
+//!
+//! - `InputError::Eof` - stdin is closed (piped input exhausted, Ctrl-D) or
+//!   the input backend has no answers left
+//! - `InputError::Io` - reading from or writing to the input backend failed
+//! - `InputError::Empty` - the line was blank after trimming
+//! - `InputError::Parse` - the line could not be converted to the target type
+//! - `InputError::Invalid` - the value was rejected by a `range` or `validate`
+//!   option
+//!
+//! # Target Types
+//!
+//! Single values are converted through the `FromInput` trait, implemented
+//! for every `FromStr` type whose error implements `Display`: the primitive
+//! types, `String`, `PathBuf`, `NonZeroU32`, and types from other crates
+//! such as `Uuid`. Types of your own crate may also implement `FromInput`
+//! directly.
+//!
+//! # Structured Values
+//!
+//! Containers are converted through `FromStructuredInput`:
+//! - `Option<T>` - an empty line yields `None` instead of an `Empty` error
+//! - `Vec<T>` - elements separated by commas, or by whitespace when the line
+//!   has no comma: `1, 2, 3` or `1 2 3`
+//! - Tuples of 2 to 6 elements - same separators, exact element count:
+//!   `3 4.5` for `(i32, f64)`
+//! - `HashMap<K, V>` / `BTreeMap<K, V>` - `key=value` entries with the same
+//!   separators: `a=1, b=2`; duplicate keys are rejected
+//!
+//! Each element is converted with `FromInput`, so any `FromStr` type can be
+//! an element, and a failure names the element:
+//! ``Element 2 (`x`): invalid digit found in string``.
+//!
+//! # Choosing the Conversion
+//!
+//! The macros cannot see the target type, so they pick the trait through an
+//! `InputTarget<T>` tied to the macro's result type: `FromInput` when `T`
+//! implements it, `FromStructuredInput` otherwise. Structured targets need
+//! their type known where the macro is used, as in
+//! `let v: Vec<u32> = input!(...)`; single values may also be inferred later.
+//!
+//! A `default` is converted with `Into`, so `default = "anon"` fills a
+//! `String`. A default of the target type is used as is, which keeps
+//! `default = 8080` inferring the target's integer type. `Option<T>` targets
+//! never reach their `default`: an empty line already parses as `None`.
+//!
+//! # Input Source
+//!
+//! Lines are read through the input backend of the current thread (see the
+//! `backend` module): stdin by default, canned answers in tests, or answers
+//! from `CIO_ANSWERS`/`CIO_ANSWERS_FILE` in non-interactive runs.
+//!
+//! # Macro Behavior
+//!
+//! - `try_input!` reads a single line and returns `Result<T, InputError>`
+//! - `input!` shows `Empty`, `Parse` and `Invalid` errors and asks again; on `Eof` it
+//!   ends the line and exits the process with status 0, and on `Io` it
+//!   reports the error on stderr and exits with status 1
+
+use std::cell::Cell;
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum InputError {
+    Eof,
+    Io(io::Error),
+    Empty,
+    Parse(String),
+    Invalid(String),
+}
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Eof => write!(f, "Unexpected end of input"),
+            InputError::Io(e) => write!(f, "Input/output failed: {}", e),
+            InputError::Empty => write!(f, "Unauthorized empty input"),
+            InputError::Parse(message) | InputError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+impl From<io::Error> for InputError {
+    fn from(e: io::Error) -> Self {
+        InputError::Io(e)
+    }
+}
+pub fn read_input_line() -> Result<String, InputError> {
+    match crate::backend::read_backend_line(true)? {
+        Some(line) => Ok(line.trim().to_string()),
+        None => Err(InputError::Eof),
+    }
+}
+pub trait FromInput: Sized {
+    fn from_input(line: &str) -> Result<Self, InputError>;
+}
+impl<T: FromStr> FromInput for T
+where
+    T::Err: fmt::Display,
+{
+    fn from_input(line: &str) -> Result<Self, InputError> {
+        parse_with_from_str(line)
+    }
+}
+pub trait FromStructuredInput: Sized {
+    fn from_structured_input(line: &str) -> Result<Self, InputError>;
+}
+pub fn parse_with_from_str<T: FromStr>(line: &str) -> Result<T, InputError>
+where
+    T::Err: fmt::Display,
+{
+    if line.is_empty() {
+        return Err(InputError::Empty);
+    }
+    line.parse().map_err(|e: T::Err| InputError::Parse(e.to_string()))
+}
+impl<T: FromInput> FromStructuredInput for Option<T> {
+    fn from_structured_input(line: &str) -> Result<Self, InputError> {
+        if line.is_empty() {
+            return Ok(None);
+        }
+        T::from_input(line).map(Some)
+    }
+}
+fn split_elements(line: &str) -> Vec<&str> {
+    if line.contains(',') {
+        line.split(',').map(str::trim).collect()
+    } else {
+        line.split_whitespace().collect()
+    }
+}
+fn element_error(kind: &str, position: usize, text: &str, error: InputError) -> InputError {
+    let message = match error {
+        InputError::Empty => format!("{} {} is empty", kind, position),
+        InputError::Parse(message) | InputError::Invalid(message) => format!("{} {} (`{}`): {}", kind, position, text, message),
+        other => return other,
+    };
+    InputError::Parse(message)
+}
+fn parse_element<T: FromInput>(kind: &str, position: usize, text: &str) -> Result<T, InputError> {
+    T::from_input(text).map_err(|e| element_error(kind, position, text, e))
+}
+impl<T: FromInput> FromStructuredInput for Vec<T> {
+    fn from_structured_input(line: &str) -> Result<Self, InputError> {
+        if line.is_empty() {
+            return Err(InputError::Empty);
+        }
+        split_elements(line).into_iter().enumerate()
+            .map(|(i, text)| parse_element("Element", i + 1, text))
+            .collect()
+    }
+}
+macro_rules! impl_from_input_tuple {
+    ($count:literal: $($t:ident),+) => {
+        impl<$($t: FromInput),+> FromStructuredInput for ($($t,)+) {
+            fn from_structured_input(line: &str) -> Result<Self, InputError> {
+                if line.is_empty() {
+                    return Err(InputError::Empty);
+                }
+                let elements = split_elements(line);
+                if elements.len() != $count {
+                    return Err(InputError::Parse(format!("Expected {} values, got {}", $count, elements.len())));
+                }
+                let mut elements = elements.into_iter().enumerate();
+                Ok(($({
+                    let (i, text) = elements.next().expect("element count checked");
+                    parse_element::<$t>("Element", i + 1, text)?
+                },)+))
+            }
+        }
+    };
+}
+impl_from_input_tuple!(2: A, B);
+impl_from_input_tuple!(3: A, B, C);
+impl_from_input_tuple!(4: A, B, C, D);
+impl_from_input_tuple!(5: A, B, C, D, E);
+impl_from_input_tuple!(6: A, B, C, D, E, F);
+fn parse_entries<K: FromInput + PartialEq + fmt::Display, V: FromInput>(line: &str) -> Result<Vec<(K, V)>, InputError> {
+    if line.is_empty() {
+        return Err(InputError::Empty);
+    }
+    let mut entries: Vec<(K, V)> = Vec::new();
+    for (i, text) in split_elements(line).into_iter().enumerate() {
+        let Some((key, value)) = text.split_once('=') else {
+            return Err(InputError::Parse(format!("Entry {} (`{}`): expected `key=value`", i + 1, text)));
+        };
+        let key: K = parse_element("Key of entry", i + 1, key.trim())?;
+        if entries.iter().any(|(k, _)| *k == key) {
+            return Err(InputError::Parse(format!("Entry {}: duplicate key `{}`", i + 1, key)));
+        }
+        entries.push((key, parse_element("Value of entry", i + 1, value.trim())?));
+    }
+    Ok(entries)
+}
+impl<K, V, S> FromStructuredInput for std::collections::HashMap<K, V, S>
+where
+    K: FromInput + Eq + std::hash::Hash + fmt::Display,
+    V: FromInput,
+    S: std::hash::BuildHasher + Default,
+{
+    fn from_structured_input(line: &str) -> Result<Self, InputError> {
+        parse_entries(line).map(|entries| entries.into_iter().collect())
+    }
+}
+impl<K: FromInput + Ord + fmt::Display, V: FromInput> FromStructuredInput for std::collections::BTreeMap<K, V> {
+    fn from_structured_input(line: &str) -> Result<Self, InputError> {
+        parse_entries(line).map(|entries| entries.into_iter().collect())
+    }
+}
+pub fn parse_input<T: FromInput>(line: &str) -> Result<T, InputError> {
+    T::from_input(line)
+}
+pub struct InputTarget<T>(PhantomData<T>);
+impl<T> InputTarget<T> {
+    pub fn new() -> Self {
+        InputTarget(PhantomData)
+    }
+    pub fn placeholder(&self) -> T {
+        unreachable!("InputTarget::placeholder only ties the target type to the macro result")
+    }
+    pub fn default_value<D>(&self, value: D) -> DefaultValue<D, T> {
+        DefaultValue(Cell::new(Some(value)), PhantomData)
+    }
+}
+impl<T> Default for InputTarget<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+pub trait ScalarTarget<T> {
+    fn parse_answer(&self, line: &str) -> Result<T, InputError>;
+}
+impl<T: FromInput> ScalarTarget<T> for InputTarget<T> {
+    fn parse_answer(&self, line: &str) -> Result<T, InputError> {
+        T::from_input(line)
+    }
+}
+pub trait StructuredTarget<T> {
+    fn parse_answer(&self, line: &str) -> Result<T, InputError>;
+}
+impl<T: FromStructuredInput> StructuredTarget<T> for &InputTarget<T> {
+    fn parse_answer(&self, line: &str) -> Result<T, InputError> {
+        T::from_structured_input(line)
+    }
+}
+pub struct DefaultValue<D, T>(Cell<Option<D>>, PhantomData<T>);
+pub trait SameTypeDefault<T> {
+    fn take_default(&self) -> T;
+}
+impl<T> SameTypeDefault<T> for DefaultValue<T, T> {
+    fn take_default(&self) -> T {
+        self.0.take().expect("default taken once")
+    }
+}
+pub trait ConvertedDefault<T> {
+    fn take_default(&self) -> T;
+}
+impl<D: Into<T>, T> ConvertedDefault<T> for &DefaultValue<D, T> {
+    fn take_default(&self) -> T {
+        self.0.take().expect("default taken once").into()
+    }
+}
+pub fn check_range<T: PartialOrd + fmt::Display, R: RangeBounds<T>>(value: T, range: &R) -> Result<T, InputError> {
+    if range.contains(&value) {
+        return Ok(value);
+    }
+    let message = match (range.start_bound(), range.end_bound()) {
+        (Bound::Included(low), Bound::Included(high)) => format!("Value must be between {} and {}", low, high),
+        (Bound::Included(low), Bound::Excluded(high)) => format!("Value must be at least {} and less than {}", low, high),
+        (Bound::Included(low), Bound::Unbounded) => format!("Value must be at least {}", low),
+        (Bound::Unbounded, Bound::Included(high)) => format!("Value must be at most {}", high),
+        (Bound::Unbounded, Bound::Excluded(high)) => format!("Value must be less than {}", high),
+        _ => "Value is out of range".to_string(),
+    };
+    Err(InputError::Invalid(message))
+}
+pub fn check_input<T>(value: T, validate: impl FnOnce(&T) -> bool, message: impl fmt::Display) -> Result<T, InputError> {
+    if validate(&value) {
+        Ok(value)
+    } else {
+        Err(InputError::Invalid(message.to_string()))
+    }
+}
+#[cfg(test)]
+pub(crate) fn error_message<T: fmt::Debug>(result: Result<T, InputError>) -> String {
+    match result {
+        Err(InputError::Parse(message) | InputError::Invalid(message)) => message,
+        other => panic!("expected a parse or validation error, got {:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_errors_describe_the_bounds() {
+        assert_eq!(error_message(check_range(0, &(1..=10))), "Value must be between 1 and 10");
+        assert_eq!(error_message(check_range(10, &(1..10))), "Value must be at least 1 and less than 10");
+        assert_eq!(error_message(check_range(-1, &(0..))), "Value must be at least 0");
+        assert_eq!(error_message(check_range(11, &(..=10))), "Value must be at most 10");
+        assert_eq!(error_message(check_range(10, &(..10))), "Value must be less than 10");
+        assert_eq!(error_message(check_range(1.5, &(2.0..=3.0))), "Value must be between 2 and 3");
+    }
+
+    #[test]
+    fn values_in_range_are_kept() {
+        assert_eq!(check_range(5, &(1..=10)).unwrap(), 5);
+        assert_eq!(check_range(1, &(1..10)).unwrap(), 1);
+        assert_eq!(check_range(-7, &(..)).unwrap(), -7);
+    }
+
+    #[test]
+    fn vec_errors_name_the_element() {
+        assert_eq!(Vec::<u8>::from_structured_input("1, 2, 3").unwrap(), [1, 2, 3]);
+        assert_eq!(Vec::<u8>::from_structured_input("4 5").unwrap(), [4, 5]);
+        assert_eq!(error_message(Vec::<u8>::from_structured_input("1, x, 3")), "Element 2 (`x`): invalid digit found in string");
+        assert_eq!(error_message(Vec::<u8>::from_structured_input("1,,3")), "Element 2 is empty");
+        assert!(matches!(Vec::<u8>::from_structured_input(""), Err(InputError::Empty)));
+    }
+
+    #[test]
+    fn tuple_errors_name_the_element_and_count() {
+        assert_eq!(<(i32, f64)>::from_structured_input("3 4.5").unwrap(), (3, 4.5));
+        assert_eq!(error_message(<(i32, f64)>::from_structured_input("3, y")), "Element 2 (`y`): invalid float literal");
+        assert_eq!(error_message(<(i32, f64)>::from_structured_input("3")), "Expected 2 values, got 1");
+        assert_eq!(error_message(<(i32, f64, char)>::from_structured_input("1 2 3 4")), "Expected 3 values, got 4");
+    }
+
+    #[test]
+    fn map_errors_name_the_entry() {
+        let map = std::collections::BTreeMap::<String, std::num::NonZeroU32>::from_structured_input("cpu=4, mem=512").unwrap();
+        assert_eq!(map["mem"].get(), 512);
+        type Limits = std::collections::HashMap<String, u32>;
+        assert_eq!(error_message(Limits::from_structured_input("cpu")), "Entry 1 (`cpu`): expected `key=value`");
+        assert_eq!(error_message(Limits::from_structured_input("a=1, a=2")), "Entry 2: duplicate key `a`");
+        assert_eq!(error_message(Limits::from_structured_input("a=1, b=x")), "Value of entry 2 (`x`): invalid digit found in string");
+    }
+
+    #[test]
+    fn defaults_of_the_target_type_are_used_as_is() {
+        let port: u16 = InputTarget::<u16>::new().default_value(8080).take_default();
+        assert_eq!(port, 8080);
+        let ratio = InputTarget::<f32>::new().default_value(0.5).take_default();
+        assert_eq!(ratio, 0.5f32);
+    }
+
+    #[test]
+    fn other_defaults_are_converted_with_into() {
+        let host: String = (&InputTarget::<String>::new().default_value("localhost")).take_default();
+        assert_eq!(host, "localhost");
+        let scale: f64 = (&InputTarget::<f64>::new().default_value(2u8)).take_default();
+        assert_eq!(scale, 2.0);
+    }
+
+    #[test]
+    fn failed_validation_uses_the_message() {
+        assert_eq!(error_message(check_input(String::from("ab"), |s: &String| s.len() >= 3, "Too short")), "Too short");
+        assert!(matches!(check_input(3, |n: &i32| *n > 0, "Positive"), Ok(3)));
+    }
+}
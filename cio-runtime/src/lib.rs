@@ -0,0 +1,116 @@
+//! Runtime support library for the CIO macros.
+//!
+//! This crate serves as the central orchestrator for all formatting operations,
+//! delegating to specialized formatting modules and coordinating the global
+//! formatting system. The `println!` and `input!` macros expand to calls into
+//! this crate (re-exported as `cio::runtime`) instead of inlining the helpers
+//! into every call site.
+//!
+//! # Architecture
+//!
+//! The crate follows a modular architecture where each formatting category
+//! is handled by dedicated sub-modules:
+//!
+//! - **Common Functions**: Shared utilities used across all formatters
+//! - **Basic Formatting**: Standard container and structure formatting 
+//! - **Mathematical Formatting**: Matrix, determinant, and mathematical notation
+//! - **Table Formatting**: Advanced table rendering with hierarchical colors
+//!
+//! # Module Organization
+//!
+//! Each specialized formatter is implemented as its own module:
+//! - `common` - Shared helper functions and utilities
+//! - `basic` - Basic formatters (`:a`, `:c`, `:j`)
+//! - `math` - Mathematical formatters (`:m`, `:d`)
+//! - `table` - Table formatters (`:t`, `:t(headers)`)
+//! - `export` - Table export (`:md`, `:csv`, `:tsv`, `:html`)
+//! - `colorstyle` - ANSI color and style engine
+//! - `colormode` - Color mode selection (auto/always/never) per output stream
+//! - `backend` - Pluggable input/output for the interactive macros
+//! - `editor` - Line editing and history for prompts (`line-editor` feature)
+//! - `input` - Line reading and conversion for `input!` / `try_input!`
+//! - `retry` - Retry policy and error messages for the interactive macros
+//! - `confirm` - Yes/no answers for `confirm!`
+//! - `secret` - Echo-free reading for `input!(..., secret)`
+//! - `select` - Choice lists for `select!` / `multiselect!`
+//!
+//! # Design Philosophy
+//!
+//! The extension system is designed with several key principles:
+//!
+//! ## Modularity
+//! Each formatting category is self-contained, allowing for easy maintenance
+//! and testing of individual components without affecting others.
+//!
+//! ## Performance
+//! All helper functions are compiled once in this crate, keeping macro
+//! expansions small and compile times independent of the number of call sites.
+//!
+//! ## Extensibility  
+//! New formatting capabilities can be added by creating new modules
+//! and re-exporting their entry points from this crate root.
+//!
+//! ## Type Safety
+//! All formatters work with Rust's type system, providing compile-time
+//! guarantees about formatting operations.
+//!
+//! # Usage Pattern
+//!
+//! The crate is used by the code generated by the `formatext` module:
+//! 1. Format tokens are parsed and classified
+//! 2. Appropriate formatters are selected based on format specifiers
+//! 3. Calls to `cio::runtime` functions are emitted in the generated code
+//! 4. Final output is assembled and rendered
+//!
+//! # Integration Points
+//!
+//! The module integrates with:
+//! - **Macro System**: Provides functions for code generation
+//! - **Color System**: Coordinates with ANSI color formatting
+//! - **Extension Points**: Allows for future formatting additions
+//! - **Error Handling**: Provides consistent error reporting across formatters
+//!
+//! # Performance Characteristics
+//!
+//! - **Single Copy**: Helpers are compiled once, not per macro invocation
+//! - **Minimal Allocations**: Efficient string building strategies
+//! - **Type Erasure**: Generic implementations for maximum flexibility
+//!
+//! # Future Extensions
+//!
+//! The architecture supports additional formatting categories:
+//! - Custom user-defined formatters
+//! - Domain-specific formatting (dates, currencies, etc.)
+//! - More export formats (JSON, XML)
+
+mod common;
+mod basic;
+mod math;
+mod table;
+mod export;
+mod colorstyle;
+mod colormode;
+mod backend;
+mod editor;
+mod input;
+mod retry;
+mod confirm;
+mod secret;
+mod select;
+
+pub use basic::format_container;
+pub use colorstyle::{ansi_code_for_style, split_style_list, Attribute, Color, Style, ToStyle};
+pub use colormode::{apply_color_mode, color_level, color_mode, colors_enabled, set_color_mode, ColorLevel, ColorMode, OutputStream};
+pub use backend::{reset_input_backend, set_input_backend, with_input_backend, write_output, InputBackend, Scripted, Terminal};
+pub use editor::{read_prompted_line, LineOptions};
+pub use confirm::{add_confirm_words, confirm_hint, parse_confirm};
+pub use input::{
+    check_input, check_range, parse_input, parse_with_from_str, read_input_line, ConvertedDefault, DefaultValue, FromInput,
+    FromStructuredInput, InputError, InputTarget, SameTypeDefault, ScalarTarget, StructuredTarget,
+};
+pub use retry::{retry_policy, set_retry_policy, RetryPolicy, DEFAULT_ERROR_TEMPLATE};
+pub use secret::read_secret_line;
+pub use select::{parse_choice, parse_choices, render_choices};
+pub use math::{format_determinant, format_matrix};
+pub use export::{export_table, export_table_with, TableFormat};
+pub use table::{format_table, format_table_with, set_table_style, table_style, BorderStyle, Labels, Overflow, TableOptions};
\ No newline at end of file
@@ -0,0 +1,223 @@
+//! Retry policy for the interactive macros.
+//!
+//! When an answer is rejected (empty, unparsable or invalid), the macros
+//! report the error and ask again. A `RetryPolicy` decides how the error is
+//! shown and how many attempts are allowed. The global policy applies to
+//! `input!`, `select!`, `multiselect!` and `confirm!`; `input!` and
+//! `try_input!` can override it per call with `attempts`, `error` and `blink`.
+//!
+//! # Settings
+//!
+//! - `max_attempts(n)` - give up after `n` rejected answers (default: never);
+//!   `n` must be at least 1, `max_attempts(0)` panics
+//! - `error_template(t)` - message template, `{e}` is replaced by the error
+//! - `blink(false)` - drop the blink attribute from error messages
+//!
+//! # Templates
+//!
+//! Templates use the cio style syntax, rendered at runtime:
+//! ```text
+//! @(red, bold, blink)Error: {e}.@()      (default)
+//! @(yellow)⚠ @(bold)[{e}], try again
+//! ```
+//! Supported: `@(styles)`, `@()`, scopes `@(styles)[...]`, `{e}` and the
+//! `{{`, `}}`, `@@(` escapes. As in format strings, `@(styles)[[` sets the
+//! style and writes a literal `[` instead of opening a scope.
+//!
+//! A string literal given to `error =` is checked at compile time like any
+//! other format string. Templates passed to `error_template` at runtime are
+//! lenient instead: unknown style names are ignored, an unclosed scope runs
+//! to the end of the template, and placeholders other than `{e}` or an
+//! unclosed `@(` are written as text.
+//!
+//! # When Attempts Run Out
+//!
+//! - `input!`, `select!`, `multiselect!`, `confirm!` - render "Too many
+//!   invalid attempts" with the error template on stderr and exit with
+//!   status 1
+//! - `try_input!(..., attempts = n)` - return the last error
+
+use crate::backend::write_output;
+use crate::colormode::{apply_color_mode, OutputStream};
+use crate::colorstyle::{Attribute, Style};
+use crate::input::InputError;
+use std::sync::RwLock;
+
+pub const DEFAULT_ERROR_TEMPLATE: &str = "@(red, bold, blink)Error: {e}.@()";
+const EXHAUSTED_MESSAGE: &str = "Too many invalid attempts";
+const RESET_CODE: &str = "\x1B[0m";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: Option<usize>,
+    error_template: String,
+    blink: bool,
+}
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy { max_attempts: None, error_template: DEFAULT_ERROR_TEMPLATE.to_string(), blink: true }
+    }
+}
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        assert!(attempts > 0, "max_attempts must be at least 1");
+        self.max_attempts = Some(attempts);
+        self
+    }
+    pub fn unlimited(mut self) -> Self {
+        self.max_attempts = None;
+        self
+    }
+    pub fn error_template(mut self, template: impl Into<String>) -> Self {
+        self.error_template = template.into();
+        self
+    }
+    pub fn blink(mut self, blink: bool) -> Self {
+        self.blink = blink;
+        self
+    }
+    pub fn attempt_limit(&self) -> Option<usize> {
+        self.max_attempts
+    }
+    pub fn is_exhausted(&self, failures: usize) -> bool {
+        self.max_attempts.is_some_and(|max| failures >= max)
+    }
+    pub fn render_error(&self, error: &InputError) -> String {
+        render_template(&self.error_template, &error.to_string(), self.blink)
+    }
+    pub fn report(&self, error: &InputError) -> Result<(), InputError> {
+        write_output(&apply_color_mode(format!("{}\n", self.render_error(error)), OutputStream::Stdout))
+    }
+    pub fn report_exhausted(&self) {
+        let message = render_template(&self.error_template, EXHAUSTED_MESSAGE, self.blink);
+        eprintln!("{}", apply_color_mode(message, OutputStream::Stderr));
+    }
+}
+static RETRY_POLICY: RwLock<Option<RetryPolicy>> = RwLock::new(None);
+pub fn set_retry_policy(policy: RetryPolicy) {
+    *RETRY_POLICY.write().unwrap_or_else(|e| e.into_inner()) = Some(policy);
+}
+pub fn retry_policy() -> RetryPolicy {
+    RETRY_POLICY.read().unwrap_or_else(|e| e.into_inner()).clone().unwrap_or_default()
+}
+fn find_closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(i),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+fn style_code(style: &Style, blink: bool) -> String {
+    let style = if blink { style.clone() } else { style.clone().without(Attribute::Blink) };
+    if style.is_plain() {
+        RESET_CODE.to_string()
+    } else {
+        format!("{}{}", RESET_CODE, style.ansi_code())
+    }
+}
+fn render_template(template: &str, error: &str, blink: bool) -> String {
+    let mut output = String::new();
+    let mut current = Style::new();
+    let mut scopes: Vec<(Style, Style, usize)> = Vec::new();
+    let mut i = 0;
+    while i < template.len() {
+        let rest = &template[i..];
+        if let Some(escape) = ["{{", "}}", "@@("].iter().find(|e| rest.starts_with(**e)) {
+            output.push_str(&escape[1..]);
+            i += escape.len();
+        } else if rest.starts_with("{e}") {
+            output.push_str(error);
+            i += 3;
+        } else if rest.starts_with("@(") && let Some(close) = find_closing_paren(&rest[2..]) {
+            let base = scopes.last().map(|(base, _, _)| base.clone()).unwrap_or_default();
+            let list = &rest[2..2 + close];
+            let escaped_bracket = rest[2 + close + 1..].starts_with("[[");
+            let opens_scope = rest[2 + close + 1..].starts_with('[') && !escaped_bracket && !list.trim().is_empty();
+            let outer = if opens_scope { &current } else { &base };
+            let style = outer.patch(&Style::parse_lossy(list));
+            let previous = std::mem::replace(&mut current, style);
+            if opens_scope {
+                scopes.push((current.clone(), previous, 0));
+            }
+            output.push_str(&style_code(&current, blink));
+            i += 2 + close + 1 + usize::from(opens_scope || escaped_bracket);
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            match (c, scopes.last_mut()) {
+                (']', Some((_, _, 0))) => {
+                    let (_, previous, _) = scopes.pop().expect("scope is open");
+                    current = previous;
+                    output.push_str(&style_code(&current, blink));
+                },
+                ('[', Some((_, _, depth))) => {
+                    *depth += 1;
+                    output.push(c);
+                },
+                (']', Some((_, _, depth))) => {
+                    *depth -= 1;
+                    output.push(c);
+                },
+                _ => output.push(c),
+            }
+            i += c.len_utf8();
+        }
+    }
+    if !current.is_plain() {
+        output.push_str(RESET_CODE);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_restore_the_outer_style() {
+        assert_eq!(render_template("@(red)[{e}] done", "bad", true), "\x1B[0m\x1B[31mbad\x1B[0m done");
+        assert_eq!(render_template("@(red)[a [b] c]", "", true), "\x1B[0m\x1B[31ma [b] c\x1B[0m");
+    }
+
+    #[test]
+    fn reset_inside_a_scope_returns_to_the_scope_style() {
+        assert_eq!(
+            render_template("@(bold)[a @(red)b@() c] d", "", true),
+            "\x1B[0m\x1B[1ma \x1B[0m\x1B[31;1mb\x1B[0m\x1B[1m c\x1B[0m d",
+        );
+    }
+
+    #[test]
+    fn escapes_produce_literal_text() {
+        assert_eq!(render_template("{{e}} @@(red) {e}", "bad", true), "{e} @(red) bad");
+        assert_eq!(render_template("@(red)[[{e}]", "bad", true), "\x1B[0m\x1B[31m[bad]\x1B[0m");
+        assert_eq!(render_template("@(bold)[@(red)[[{e}] ok] done", "bad", true),
+                   "\x1B[0m\x1B[1m\x1B[0m\x1B[31;1m[bad] ok\x1B[0m done");
+    }
+
+    #[test]
+    fn blink_false_only_drops_blink() {
+        assert_eq!(render_template("@(red, bold, blink){e}", "bad", false), "\x1B[0m\x1B[31;1mbad\x1B[0m");
+        assert_eq!(render_template("@(red, bold, blink){e}", "bad", true), "\x1B[0m\x1B[31;1;5mbad\x1B[0m");
+    }
+
+    #[test]
+    #[should_panic(expected = "max_attempts must be at least 1")]
+    fn zero_attempts_are_rejected() {
+        let _ = RetryPolicy::new().max_attempts(0);
+    }
+
+    #[test]
+    fn runtime_templates_are_lenient() {
+        assert_eq!(render_template("@(red, sparkly){e}", "bad", true), "\x1B[0m\x1B[31mbad\x1B[0m");
+        assert_eq!(render_template("{x} @(red", "bad", true), "{x} @(red");
+        assert_eq!(render_template("@(red)[{e}", "bad", true), "\x1B[0m\x1B[31mbad\x1B[0m");
+    }
+}
@@ -0,0 +1,229 @@
+//! Hidden input for `input!(..., secret)`.
+//!
+//! Passwords and tokens are read with terminal echo turned off, so the typed
+//! text never appears on screen. With a mask character (`secret = '*'`) the
+//! terminal is also switched to non-canonical mode and the mask is printed
+//! once per typed character, with backspace support.
+//!
+//! # Terminal Restoration
+//!
+//! The previous terminal settings are restored:
+//! - When the line has been read
+//! - When the reading code unwinds (guard dropped during a panic)
+//! - When Ctrl-C is pressed: a temporary `SIGINT` handler restores the
+//!   settings, reinstalls the previous handler and re-raises the signal
+//!
+//! # Platform Support
+//!
+//! Echo control uses termios and is available on Unix. Elsewhere, when stdin
+//! is not a terminal (piped input) and when a non-interactive input backend
+//! is active (tests, `CIO_ANSWERS`), the line is read normally and the answer
+//! is not echoed to the transcript.
+//! In every case only the line ending is removed: leading and trailing
+//! spaces are part of a secret.
+//!
+//! # Masked Keys
+//!
+//! With a mask, bytes are handled one at a time:
+//! - Enter ends the line, Backspace removes the last character
+//! - Ctrl-D ends input on an empty line and is ignored otherwise
+//! - Escape sequences (arrows, Home, Delete, ...) and other control bytes
+//!   are dropped: they are neither stored nor masked
+
+use crate::backend::{backend_is_interactive, read_backend_line};
+use crate::input::InputError;
+use std::io::{self, Write};
+
+fn read_raw_line() -> Result<String, InputError> {
+    read_backend_line(false)?.ok_or(InputError::Eof)
+}
+pub fn read_secret_line(mask: Option<char>) -> Result<String, InputError> {
+    #[cfg(unix)]
+    {
+        if backend_is_interactive() && let Some(guard) = unix::EchoGuard::disable(mask.is_none()) {
+            let line = match mask {
+                Some(mask) => unix::read_masked(mask),
+                None => read_raw_line(),
+            };
+            drop(guard);
+            println!();
+            return line;
+        }
+    }
+    let _ = mask;
+    read_raw_line()
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::io::Read;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static SAVED_TERMIOS: Mutex<Option<libc::termios>> = Mutex::new(None);
+    static PREVIOUS_HANDLER: AtomicUsize = AtomicUsize::new(libc::SIG_DFL);
+
+    pub struct EchoGuard;
+    impl EchoGuard {
+        pub fn disable(canonical: bool) -> Option<EchoGuard> {
+            let fd = libc::STDIN_FILENO;
+            let mut original: libc::termios = unsafe { std::mem::zeroed() };
+            if unsafe { libc::isatty(fd) } == 0 || unsafe { libc::tcgetattr(fd, &mut original) } != 0 {
+                return None;
+            }
+            let mut hidden = original;
+            hidden.c_lflag &= !(libc::ECHO | libc::ECHONL);
+            if !canonical {
+                hidden.c_lflag &= !libc::ICANON;
+                hidden.c_cc[libc::VMIN] = 1;
+                hidden.c_cc[libc::VTIME] = 0;
+            }
+            *SAVED_TERMIOS.lock().unwrap_or_else(|e| e.into_inner()) = Some(original);
+            let handler = restore_and_reraise as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            PREVIOUS_HANDLER.store(unsafe { libc::signal(libc::SIGINT, handler) }, Ordering::SeqCst);
+            if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &hidden) } != 0 {
+                drop(EchoGuard);
+                return None;
+            }
+            Some(EchoGuard)
+        }
+    }
+    impl Drop for EchoGuard {
+        fn drop(&mut self) {
+            restore_termios();
+            unsafe { libc::signal(libc::SIGINT, PREVIOUS_HANDLER.load(Ordering::SeqCst)) };
+        }
+    }
+    fn restore_termios() {
+        if let Ok(mut saved) = SAVED_TERMIOS.try_lock()
+            && let Some(original) = saved.take() {
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &original) };
+        }
+    }
+    extern "C" fn restore_and_reraise(signal: libc::c_int) {
+        restore_termios();
+        unsafe {
+            libc::signal(signal, PREVIOUS_HANDLER.load(Ordering::SeqCst));
+            libc::raise(signal);
+        }
+    }
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum MaskStep {
+        Echo(String),
+        Done,
+        Eof,
+    }
+    #[derive(Clone, Copy, Default, PartialEq, Eq)]
+    enum Escape {
+        #[default]
+        None,
+        Start,
+        Sequence,
+    }
+    #[derive(Default)]
+    pub struct MaskedLine {
+        bytes: Vec<u8>,
+        escape: Escape,
+    }
+    impl MaskedLine {
+        pub fn feed(&mut self, byte: u8, mask: char) -> MaskStep {
+            match (self.escape, byte) {
+                (Escape::Start, b'[' | b'O') => self.escape = Escape::Sequence,
+                (Escape::Start, _) | (Escape::Sequence, 0x40..=0x7e) => self.escape = Escape::None,
+                (Escape::Sequence, _) => {},
+                (Escape::None, b'\n' | b'\r') => return MaskStep::Done,
+                (Escape::None, 0x04) if self.bytes.is_empty() => return MaskStep::Eof,
+                (Escape::None, 0x1b) => self.escape = Escape::Start,
+                (Escape::None, 0x7f | 0x08) => {
+                    while let Some(b) = self.bytes.pop() {
+                        if b & 0xC0 != 0x80 {
+                            return MaskStep::Echo("\x08 \x08".to_string());
+                        }
+                    }
+                },
+                (Escape::None, 0x00..=0x1f) => {},
+                (Escape::None, b) => {
+                    self.bytes.push(b);
+                    if b & 0xC0 != 0x80 {
+                        return MaskStep::Echo(mask.to_string());
+                    }
+                },
+            }
+            MaskStep::Echo(String::new())
+        }
+        pub fn finish(self) -> Result<String, InputError> {
+            String::from_utf8(self.bytes).map_err(|e| InputError::Parse(e.to_string()))
+        }
+    }
+    pub fn read_masked(mask: char) -> Result<String, InputError> {
+        let mut stdout = io::stdout();
+        let mut line = MaskedLine::default();
+        let mut stdin = io::stdin().lock();
+        let mut byte = [0u8; 1];
+        loop {
+            if stdin.read(&mut byte)? == 0 {
+                return Err(InputError::Eof);
+            }
+            match line.feed(byte[0], mask) {
+                MaskStep::Echo(echo) => print!("{}", echo),
+                MaskStep::Done => break,
+                MaskStep::Eof => return Err(InputError::Eof),
+            }
+            stdout.flush()?;
+        }
+        line.finish()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::unix::{MaskStep, MaskedLine};
+
+    fn type_keys(keys: &[u8]) -> (String, Option<MaskStep>) {
+        let mut line = MaskedLine::default();
+        let mut echo = String::new();
+        for &byte in keys {
+            match line.feed(byte, '*') {
+                MaskStep::Echo(text) => echo.push_str(&text),
+                step => return (echo, Some(step)),
+            }
+        }
+        (echo, None)
+    }
+    fn typed(keys: &[u8]) -> String {
+        let mut line = MaskedLine::default();
+        for &byte in keys {
+            if line.feed(byte, '*') == MaskStep::Done {
+                break;
+            }
+        }
+        line.finish().expect("valid UTF-8")
+    }
+
+    #[test]
+    fn characters_are_masked_once_each() {
+        assert_eq!(type_keys("pé€\n".as_bytes()), ("***".to_string(), Some(MaskStep::Done)));
+        assert_eq!(typed("pé€\n".as_bytes()), "pé€");
+    }
+
+    #[test]
+    fn backspace_removes_whole_characters() {
+        assert_eq!(type_keys("aé\x7f\x7f\x7f".as_bytes()), ("**\x08 \x08\x08 \x08".to_string(), None));
+        assert_eq!(typed("aé\x08b\r".as_bytes()), "ab");
+    }
+
+    #[test]
+    fn ctrl_d_ends_input_only_on_an_empty_line() {
+        assert_eq!(type_keys(b"\x04"), (String::new(), Some(MaskStep::Eof)));
+        assert_eq!(type_keys(b"ab\x04"), ("**".to_string(), None));
+        assert_eq!(typed(b"ab\x04c\n"), "abc");
+    }
+
+    #[test]
+    fn escape_sequences_are_dropped() {
+        let keys = b"a\x1b[D\x1b[H\x1b[3~\x1bOFb\x1bx\tc\n";
+        assert_eq!(type_keys(keys), ("***".to_string(), Some(MaskStep::Done)));
+        assert_eq!(typed(keys), "abc");
+    }
+}
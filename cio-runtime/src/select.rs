@@ -0,0 +1,144 @@
+//! Runtime support for the `select!` and `multiselect!` macros.
+//!
+//! The macros collect the options, turn each one into its `Display` text and
+//! call into this module to render the numbered list and to interpret the
+//! answers. Reading, retrying and EOF handling are shared with `input!`.
+//!
+//! # Accepted Answers
+//!
+//! ## `select!`
+//! - A number between 1 and the number of options
+//! - A case-insensitive prefix of an option's text, as long as it matches a
+//!   single option (an exact match wins over longer options)
+//!
+//! ## `multiselect!`
+//! - A comma-separated list of numbers, prefixes and ranges: `1,3,5-7`
+//! - Duplicates are ignored and the selection is returned in list order
+//!
+//! # Rendering
+//!
+//! ```text
+//!   1) Apple
+//!   2) Banana
+//!  10) Cherry
+//! ```
+//! Numbers are right-aligned and shown in bright cyan bold; the color mode of
+//! the `format!` call that renders the list still applies.
+
+use crate::input::InputError;
+
+const RESET_CODE: &str = "\x1B[0m";
+const NUMBER_COLOR: &str = "\x1B[96;1m";
+
+pub fn render_choices(labels: &[String]) -> String {
+    let width = labels.len().to_string().len();
+    labels.iter().enumerate()
+        .map(|(i, label)| format!("  {}{:>width$}){} {}\n", NUMBER_COLOR, i + 1, RESET_CODE, label, width = width))
+        .collect()
+}
+fn parse_number(text: &str, count: usize) -> Option<Result<usize, InputError>> {
+    let number = text.parse::<usize>().ok()?;
+    Some(if (1..=count).contains(&number) {
+        Ok(number - 1)
+    } else {
+        Err(InputError::Invalid(format!("Choice must be between 1 and {}", count)))
+    })
+}
+fn parse_label(text: &str, labels: &[String]) -> Result<usize, InputError> {
+    let wanted = text.to_lowercase();
+    if let Some(exact) = labels.iter().position(|label| label.to_lowercase() == wanted) {
+        return Ok(exact);
+    }
+    let matches: Vec<usize> = (0..labels.len())
+        .filter(|&i| labels[i].to_lowercase().starts_with(&wanted))
+        .collect();
+    match matches[..] {
+        [index] => Ok(index),
+        [] => Err(InputError::Invalid(format!("No option matches `{}`", text))),
+        _ => Err(InputError::Invalid(format!("`{}` matches several options: {}", text,
+            matches.iter().map(|&i| labels[i].as_str()).collect::<Vec<_>>().join(", ")))),
+    }
+}
+pub fn parse_choice(line: &str, labels: &[String]) -> Result<usize, InputError> {
+    let text = line.trim();
+    if text.is_empty() {
+        return Err(InputError::Empty);
+    }
+    parse_number(text, labels.len()).unwrap_or_else(|| parse_label(text, labels))
+}
+pub fn parse_choices(line: &str, labels: &[String]) -> Result<Vec<usize>, InputError> {
+    if line.trim().is_empty() {
+        return Err(InputError::Empty);
+    }
+    let mut selected = vec![false; labels.len()];
+    for part in line.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        let range = part.split_once('-').and_then(|(start, end)| {
+            Some((parse_number(start.trim(), labels.len())?, parse_number(end.trim(), labels.len())?))
+        });
+        match range {
+            Some((start, end)) => {
+                let (start, end) = (start?, end?);
+                if start > end {
+                    return Err(InputError::Invalid(format!("Range `{}` is reversed", part)));
+                }
+                selected[start..=end].iter_mut().for_each(|s| *s = true);
+            },
+            None => selected[parse_choice(part, labels)?] = true,
+        }
+    }
+    Ok((0..labels.len()).filter(|&i| selected[i]).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::error_message;
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn choices_accept_numbers_ranges_and_prefixes() {
+        let fruits = labels(&["Apple", "Banana", "Cherry", "Date", "Elderberry"]);
+        assert_eq!(parse_choices("1,3-4", &fruits).unwrap(), [0, 2, 3]);
+        assert_eq!(parse_choices(" 5 , ban ", &fruits).unwrap(), [1, 4]);
+        assert_eq!(parse_choices("2-2", &fruits).unwrap(), [1]);
+    }
+
+    #[test]
+    fn choices_ignore_duplicates_and_keep_list_order() {
+        let fruits = labels(&["Apple", "Banana", "Cherry"]);
+        assert_eq!(parse_choices("3,1,3", &fruits).unwrap(), [0, 2]);
+        assert_eq!(parse_choices("1-2,2,apple", &fruits).unwrap(), [0, 1]);
+    }
+
+    #[test]
+    fn reversed_range_is_rejected() {
+        let fruits = labels(&["Apple", "Banana", "Cherry", "Date"]);
+        assert_eq!(error_message(parse_choices("4-2", &fruits)), "Range `4-2` is reversed");
+        assert_eq!(error_message(parse_choices("1-9", &fruits)), "Choice must be between 1 and 4");
+    }
+
+    #[test]
+    fn ambiguous_prefix_lists_the_candidates() {
+        let fruits = labels(&["Apple", "Apricot", "Banana"]);
+        assert_eq!(error_message(parse_choices("ap", &fruits)), "`ap` matches several options: Apple, Apricot");
+        assert_eq!(error_message(parse_choices("kiwi", &fruits)), "No option matches `kiwi`");
+        assert_eq!(parse_choices("apr", &fruits).unwrap(), [1]);
+    }
+
+    #[test]
+    fn exact_label_wins_over_longer_options() {
+        let languages = labels(&["Go", "Gopher", "Wi-Fi"]);
+        assert_eq!(parse_choice("go", &languages).unwrap(), 0);
+        assert_eq!(parse_choices("wi-fi", &languages).unwrap(), [2]);
+    }
+
+    #[test]
+    fn empty_answer_is_empty() {
+        let fruits = labels(&["Apple"]);
+        assert!(matches!(parse_choices("  ", &fruits), Err(InputError::Empty)));
+        assert!(matches!(parse_choice("", &fruits), Err(InputError::Empty)));
+    }
+}
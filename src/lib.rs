@@ -8,15 +8,17 @@
 //! - **Enhanced println!** - ANSI colors with `@(color, style)` syntax
 //! - **Advanced table formatting** - Sophisticated layouts with `:t` format specifier
 //! - **Custom headers** - Personalized table headers with `:t(Header1, Header2)` syntax
-//! - **Type-safe input!** - *Coming soon* - Automatic parsing with validation
-//! - **Matrix display** - *Coming soon* - Beautiful formatting for 2D/3D data
+//! - **Type-safe input!** - Parsing into any `FromStr` type with defaults, ranges,
+//!   validation and retries, plus `try_input!`, `confirm!`, `select!` and `multiselect!`
+//! - **Matrix display** - Matrices (`:m`) and determinants (`:d`) for 2D/3D data
+//! - **Table export** - Markdown, CSV, TSV and HTML with `:md`, `:csv`, `:tsv`, `:html`
 //!
 //! ## Installation
 //!
 //! Add to your `Cargo.toml`:
 //! ```toml
 //! [dependencies]
-//! pyrust = "0.1.0"
+//! cio = "0.5.1"
 //! serde_json = "1.0"  # Required for JSON data formatting
 //! ```
 //!
@@ -35,6 +37,15 @@
 //! println!("@(red)Error:@() Something went wrong.");
 //! ```
 //!
+//! Reading typed answers:
+//! ```rust,no_run
+//! use cio::{confirm, input};
+//!
+//! let age: u32 = input!("@(cyan)Age: ", range = 0..=150);
+//! let port: u16 = input!("Port: ", default = 8080);
+//! if confirm!("Save?", default = true) { /* ... */ }
+//! ```
+//!
 //! ## Advanced Examples
 //!
 //!
@@ -51,6 +62,7 @@
 //! - `:t` - Smart table formatting with automatic structure detection
 //! - `:t(Col1, Col2)` - Custom column headers
 //! - `:m` - Matrix format with mathematical brackets
+//! - `:d` - Determinant format with vertical bars
 //! - `:a` - Array format with proper indentation
 //! - `:c` - Compact single-line format
 //!
@@ -65,24 +77,14 @@
 //!
 //! ## Compatibility
 //!
-//! - Rust 1.70+ required for procedural macro features
+//! - Rust 1.88+ required (edition 2024, let chains)
 //! - Cross-platform ANSI color support
 //! - Automatic fallback on unsupported terminals
 
-use proc_macro::TokenStream;
-
-mod colorstyle;
-mod extensions;
-mod formatext;
-mod println;
-mod input;
-
-#[proc_macro]
-pub fn println(input: TokenStream) -> TokenStream {
-    println::println_impl(input)
-}
+pub use cio_macros::{confirm, eprint, eprintln, format, input, multiselect, print, println, select, try_input, write, writeln};
 
-#[proc_macro]
-pub fn input(input: TokenStream) -> TokenStream {
-    input::input_impl(input)
-}
\ No newline at end of file
+/// Runtime support library called by the code the macros expand to.
+///
+/// Exposes the formatters behind the `:a`, `:m`, `:d` and `:t` specifiers and
+/// the ANSI style engine, so they can also be used directly.
+pub use cio_runtime as runtime;